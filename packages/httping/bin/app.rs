@@ -1,5 +1,8 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::io::Write;
 use std::sync::Arc;
+use std::time::Duration;
 
 use httping::Ui;
 use ratatui::crossterm::event::Event;
@@ -10,10 +13,31 @@ use ratatui::widgets::*;
 use tokio::runtime::Builder;
 use tokio::runtime::Runtime;
 
+use httping::class_text;
+use httping::find_server;
+use httping::group_respone;
+use httping::summary_row;
+use httping::Comparison;
+use httping::GroupBy;
+use httping::PingError;
 use httping::PingServer;
+use httping::Round;
+use httping::RunRecord;
+use httping::Storage;
 use httping::Task;
+use httping::TaskRespone;
+use httping::TaskStats;
+use httping::Watch;
+use httping::STATUS_SUCCESS;
+use httping::SUMMARY_HEADER;
+use itdog::Target;
+use itdog::Timing;
+use tracing::debug;
 use tracing::trace;
 
+use crate::history::avg_total_text;
+use crate::history::state_text;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum DisplayStyle {
     Table,
@@ -33,6 +57,45 @@ pub const fn cons_percentage(val: u16) -> Constraint {
     Constraint::Percentage(val)
 }
 
+/// Return the bar of cost, the cost is missing or can not be parsed is rendered as failed.
+fn cost_bar(resp: &TaskRespone, cost: Option<&Timing>) -> Bar<'static> {
+    let bar = Bar::default().text_value(String::default());
+
+    match cost.and_then(|v| Some((v, v.duration()?))) {
+        Some((cost, duration)) if resp.status() == STATUS_SUCCESS => bar
+            .value(duration.as_millis().try_into().unwrap_or(u64::MAX))
+            .label(Line::from(format!("{}s {}", cost, resp.loc())))
+            .style(Style::new().light_blue()),
+        Some((cost, _)) => bar
+            .value(0)
+            .label(Line::from(format!("{}s {}", cost, resp.loc())))
+            .style(Style::new().on_black()),
+        None => bar
+            .value(0)
+            .label(Line::from(format!("失败 {}", resp.loc())).red())
+            .style(Style::new().on_black()),
+    }
+}
+
+/// Return the average of the successful costs, it is used as the max value of chart.
+fn chart_max<'a>(
+    list: &'a [TaskRespone],
+    cost: impl Fn(&'a TaskRespone) -> Option<&'a Timing>,
+) -> u64 {
+    let costs: Vec<u64> = list
+        .iter()
+        .filter(|v| v.status() == STATUS_SUCCESS)
+        .filter_map(|v| cost(v).and_then(Timing::duration))
+        .map(|v| v.as_millis().try_into().unwrap_or(u64::MAX))
+        .collect();
+
+    if costs.is_empty() {
+        1
+    } else {
+        (costs.iter().fold(0u64, |acc, v| acc.saturating_add(*v)) / costs.len() as u64).max(1)
+    }
+}
+
 macro_rules! extract {
     ($($var:ident),+ <- $what:ident[$beg:literal .. $end:literal]) => {
         let [$($var),+] = $what[$beg..$end] else {
@@ -53,6 +116,17 @@ pub struct App {
     table_index: TableState,
     server_index: ListState,
     server_list: Vec<Arc<dyn PingServer + Send + Sync>>,
+    storage: Option<Storage>,
+    finished: HashSet<usize>,
+    show_history: bool,
+    history: Vec<RunRecord>,
+    baseline: Option<usize>,
+    show_diff: bool,
+    regression: f64,
+    watches: HashMap<usize, Watch>,
+    interval: Duration,
+    show_stats: bool,
+    group_by: Option<GroupBy>,
 }
 
 impl Default for App {
@@ -60,6 +134,17 @@ impl Default for App {
         Self {
             server_list: vec![],
             server_index: ListState::default(),
+            storage: None,
+            finished: HashSet::default(),
+            show_history: false,
+            history: vec![],
+            baseline: None,
+            show_diff: false,
+            regression: 20.0,
+            watches: HashMap::default(),
+            interval: Duration::from_secs(300),
+            show_stats: false,
+            group_by: None,
             task_list: vec![],
             task_index: ListState::default(),
             cache: String::default(),
@@ -74,29 +159,114 @@ impl Default for App {
 }
 
 impl App {
-    pub fn with_server(mut self, server: impl PingServer + Send + Sync + 'static) -> Self {
-        self.server_list.push(Arc::new(server));
+    pub fn with_servers(mut self, servers: Vec<Arc<dyn PingServer + Send + Sync>>) -> Self {
+        self.server_list.extend(servers);
         self
     }
 
-    pub fn add_server(&mut self, server: impl PingServer + Send + Sync + 'static) -> &mut Self {
-        self.server_list.push(Arc::new(server));
+    /// Save the finished tasks to the storage, and enable the history panel.
+    pub fn with_storage(mut self, storage: Storage) -> Self {
+        self.storage = Some(storage);
         self
     }
 
-    pub fn ping_host(&mut self, server_index: usize, host: String) -> &mut Self {
-        let server = self.server_list[server_index].clone();
-        let (resp_tx, resp_rx) = tokio::sync::mpsc::channel(128);
-        let (cc_tx, cc_rx) = tokio::sync::oneshot::channel();
-        let target = host.clone();
-        let handler = self
-            .runtime
-            .spawn(async move { server.ping(target, cc_rx, resp_tx).await });
-
-        self.task_list
-            .push(Task::new(host, handler, cc_tx, resp_rx));
+    /// Set the percent of slower than baseline treated as regression in the diff view.
+    pub fn with_regression(mut self, regression: f64) -> Self {
+        self.regression = regression;
+        self
+    }
+
+    /// Set the interval of watched tasks.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Reload the history of the selected task if the history panel is shown.
+    pub fn reload_history(&mut self) -> &mut Self {
+        self.history.clear();
+        if let (true, Some(storage)) = (self.show_history, &self.storage) {
+            if let Some(task) = self
+                .task_index
+                .selected()
+                .and_then(|v| self.task_list.get(v))
+            {
+                match storage.runs(Some(task.host()), 50) {
+                    Ok(runs) => self.history = runs,
+                    Err(e) => debug!("can not load history of `{}`: {}", task.host(), e),
+                }
+            }
+        }
         self
     }
+
+    /// Ping the target with the server of it or the given server, the expected status is ignored.
+    pub fn ping_target(&mut self, server_index: usize, target: &Target) -> &mut Self {
+        let host = target.host().to_string();
+        let task = match self.target_server(server_index, target) {
+            Ok(server) => Task::spawn(self.runtime.handle(), server.as_ref(), host),
+            Err(e) => Task::failed(self.runtime.handle(), host, e),
+        };
+
+        self.task_list.push(task);
+        self
+    }
+
+    /// Ping the target repeatedly on the interval.
+    pub fn watch_target(&mut self, server_index: usize, target: &Target) -> &mut Self {
+        let host = target.host().to_string();
+
+        match self.target_server(server_index, target) {
+            Ok(server) => {
+                let mut watch = Watch::new(server, host, self.interval);
+                let task = watch.spawn(self.runtime.handle());
+
+                self.watches.insert(self.task_list.len(), watch);
+                self.task_list.push(task);
+            }
+            Err(e) => {
+                let task = Task::failed(self.runtime.handle(), host, e);
+
+                self.task_list.push(task);
+            }
+        }
+        self
+    }
+
+    /// Start or stop watching the task, the watched task is re-run on the interval.
+    pub fn toggle_watch(&mut self, task_index: usize) -> &mut Self {
+        if self.watches.remove(&task_index).is_none() {
+            if let Some(task) = self.task_list.get(task_index) {
+                if let Ok(server) = find_server(&self.server_list, task.server(), None) {
+                    let mut watch = Watch::new(server, task.host().to_string(), self.interval);
+
+                    if self.finished.contains(&task_index) {
+                        watch.finish_round(task);
+                    }
+                    self.watches.insert(task_index, watch);
+                }
+            }
+        }
+        self
+    }
+
+    fn target_server(
+        &self,
+        server_index: usize,
+        target: &Target,
+    ) -> Result<Arc<dyn PingServer + Send + Sync>, PingError> {
+        let name = target
+            .server()
+            .unwrap_or_else(|| self.server_list[server_index].name());
+
+        find_server(&self.server_list, name, target.method())
+    }
+
+    fn selected_watch(&self) -> Option<&Watch> {
+        self.task_index
+            .selected()
+            .and_then(|v| self.watches.get(&v))
+    }
 }
 
 impl App {
@@ -134,7 +304,21 @@ impl App {
         let task_list = List::new(
             self.task_list
                 .iter()
-                .map(|task| Text::from(task.host().to_owned()).centered())
+                .enumerate()
+                .map(|(idx, task)| {
+                    if let Some(watch) = self.watches.get(&idx) {
+                        Text::from(format!("{} (监视 {}轮)", task.host(), watch.rounds().len()))
+                            .centered()
+                    } else if self.baseline == Some(idx) {
+                        Text::from(format!("{} (基准)", task.host())).centered()
+                    } else if task.cancelled() {
+                        Text::from(format!("{} (已取消)", task.host())).centered()
+                    } else if task.error().is_some() {
+                        Text::from(format!("{} (失败)", task.host())).centered()
+                    } else {
+                        Text::from(task.host().to_owned()).centered()
+                    }
+                })
                 .collect::<Vec<_>>(),
         )
         .block(
@@ -197,8 +381,41 @@ impl App {
             );
         }
 
-        if !self.task_list.is_empty() {
-            let selected = self.task_index.selected().unwrap_or_default();
+        let mut resp_layout = resp_layout;
+
+        if !self.show_history && !self.show_diff && self.selected_watch().is_some() {
+            let layout = Layout::vertical([cons_min(3), cons_length(12)]).split(resp_layout);
+
+            resp_layout = layout[0];
+            self.view_trend(frame, layout[1]);
+        }
+        if let (false, false, true, Some(task)) = (
+            self.show_history,
+            self.show_diff,
+            self.show_stats,
+            self.task_index
+                .selected()
+                .and_then(|v| self.task_list.get(v)),
+        ) {
+            let stats = TaskStats::new(task.respone());
+            let height = stats.phases().len() as u16 + 4;
+            let layout = Layout::vertical([cons_min(3), cons_length(height)]).split(resp_layout);
+
+            resp_layout = layout[0];
+            self.view_stats(frame, layout[1], &stats);
+        }
+        if self.show_history {
+            self.view_history(frame, resp_layout);
+        } else if self.show_diff {
+            self.view_diff(frame, resp_layout);
+        } else if let Some(group_by) = self.group_by {
+            self.view_group(frame, resp_layout, group_by);
+        } else if !self.task_list.is_empty() {
+            let selected = self
+                .task_index
+                .selected()
+                .unwrap_or_default()
+                .min(self.task_list.len() - 1);
             let task = &self.task_list[selected];
             let respone_list = task.respone();
 
@@ -212,6 +429,12 @@ impl App {
                         let widths: Vec<Constraint> = vec![];
 
                         header.extend(respone_list[0].other_name_list().iter().map(String::from));
+                        header.extend(
+                            respone_list[0]
+                                .other_info()
+                                .iter()
+                                .map(|(name, _)| name.clone()),
+                        );
                         let rows: Vec<_> = respone_list
                             .iter()
                             .map(|respone| {
@@ -219,12 +442,21 @@ impl App {
                                     Text::from(respone.loc()),
                                     Text::from(respone.ip()),
                                     Text::from(respone.status().to_string()),
-                                    Text::from(respone.total_cost()),
+                                    Text::from(respone.total_cost().text()),
                                     Text::from(respone.redirect().to_string()),
-                                    Text::from(respone.redirect_cost()),
+                                    Text::from(respone.redirect_cost().text()),
                                 ];
                                 rows.extend(
-                                    respone.other_cost_list().iter().cloned().map(Text::from),
+                                    respone
+                                        .other_cost_list()
+                                        .iter()
+                                        .map(|v| Text::from(v.text())),
+                                );
+                                rows.extend(
+                                    respone
+                                        .other_info()
+                                        .iter()
+                                        .map(|(_, value)| Text::from(value.as_str())),
                                 );
                                 Row::new(rows).height(2)
                             })
@@ -246,37 +478,12 @@ impl App {
                         frame.render_stateful_widget(table, resp_layout, &mut self.table_index);
                     }
                     DisplayStyle::Total => {
-                        let total = respone_list
-                            .iter()
-                            .map(|v| v.total_cost().replace(".", "").parse::<u64>().unwrap())
-                            .sum::<u64>();
-                        let data: Vec<_> = respone_list[self.total_index..]
+                        let max = chart_max(respone_list, |v| Some(v.total_cost()));
+                        let data: Vec<_> = respone_list
+                            .get(self.total_index..)
+                            .unwrap_or_default()
                             .iter()
-                            .map(|resp| {
-                                let cost =
-                                    resp.total_cost().replace(".", "").parse::<u64>().unwrap();
-
-                                match resp.status() {
-                                    200 => Bar::default()
-                                        .value(cost)
-                                        .text_value(String::default())
-                                        .label(Line::from(format!(
-                                            "{}s {}",
-                                            resp.total_cost(),
-                                            resp.loc()
-                                        )))
-                                        .style(Style::new().light_blue()),
-                                    _ => Bar::default()
-                                        .value(0)
-                                        .text_value(String::default())
-                                        .label(Line::from(format!(
-                                            "{}s {}",
-                                            resp.total_cost(),
-                                            resp.loc()
-                                        )))
-                                        .style(Style::new().on_black()),
-                                }
-                            })
+                            .map(|resp| cost_bar(resp, Some(resp.total_cost())))
                             .collect();
                         let bart_chart = BarChart::default()
                             .block(
@@ -288,61 +495,34 @@ impl App {
                             .bar_width(1)
                             .label_style(Style::new().white())
                             .data(BarGroup::default().bars(&data))
-                            .max(total / respone_list.len() as u64);
+                            .max(max);
 
                         frame.render_widget(bart_chart, resp_layout);
                     }
                     DisplayStyle::Chart(i) => {
-                        let total = respone_list
+                        let max = chart_max(respone_list, |v| v.other_cost_list().get(i));
+                        let data: Vec<_> = respone_list
+                            .get(self.other_index..)
+                            .unwrap_or_default()
                             .iter()
-                            .map(|v| {
-                                v.other_cost_list()[i]
-                                    .replace(".", "")
-                                    .parse::<u64>()
-                                    .unwrap()
-                            })
-                            .sum::<u64>();
-                        let data: Vec<_> = respone_list[self.other_index..]
-                            .iter()
-                            .map(|resp| {
-                                let total = resp.other_cost_list()[i]
-                                    .replace(".", "")
-                                    .parse::<u64>()
-                                    .unwrap();
-
-                                match resp.status() {
-                                    200 => Bar::default()
-                                        .value(total)
-                                        .text_value(String::default())
-                                        .label(Line::from(format!(
-                                            "{}s {}",
-                                            resp.total_cost(),
-                                            resp.loc()
-                                        )))
-                                        .style(Style::new().light_blue()),
-                                    _ => Bar::default()
-                                        .value(0)
-                                        .text_value(String::default())
-                                        .label(Line::from(format!(
-                                            "{}s {}",
-                                            resp.total_cost(),
-                                            resp.loc()
-                                        )))
-                                        .style(Style::new().on_black()),
-                                }
-                            })
+                            .map(|resp| cost_bar(resp, resp.other_cost_list().get(i)))
                             .collect();
+                        let title = respone_list[0]
+                            .other_name_list()
+                            .get(i)
+                            .cloned()
+                            .unwrap_or_default();
                         let bart_chart = BarChart::default()
                             .block(
                                 Block::bordered()
-                                    .title(respone_list[0].other_name_list()[i].to_string())
+                                    .title(title)
                                     .title_alignment(Alignment::Center),
                             )
                             .direction(Direction::Horizontal)
                             .bar_width(1)
                             .label_style(Style::new().white())
                             .data(BarGroup::default().bars(&data))
-                            .max(total / respone_list.len() as u64);
+                            .max(max);
 
                         frame.render_widget(bart_chart, resp_layout);
                     }
@@ -366,8 +546,11 @@ impl App {
 
         status.push(Span::from(format!("任务 {}/{}", task_complete, task_count)));
 
-        if let Some(selected) = self.task_index.selected() {
-            let task = &self.task_list[selected];
+        if let Some(task) = self
+            .task_index
+            .selected()
+            .and_then(|v| self.task_list.get(v))
+        {
             let resp = task.respone();
             let success = resp.iter().filter(|v| v.status() == 200).count();
 
@@ -375,6 +558,10 @@ impl App {
                 status.push(Span::from(" | "));
                 status.push(Span::from(format!("响应 {}/{}", success, resp.len())));
             }
+            if let Some(error) = task.error() {
+                status.push(Span::from(" | "));
+                status.push(Span::from(format!("错误 {}", error)).red());
+            }
         }
 
         frame.render_widget(
@@ -393,12 +580,356 @@ impl App {
                 Span::from("E(输入模式)"),
                 Span::from(" | "),
                 Span::from("M(切换图表)"),
+                Span::from(" | "),
+                Span::from("C(取消任务)"),
+                Span::from(" | "),
+                Span::from("H(历史)"),
+                Span::from(" | "),
+                Span::from("B(基准)"),
+                Span::from(" | "),
+                Span::from("D(对比)"),
+                Span::from(" | "),
+                Span::from("W(监视)"),
+                Span::from(" | "),
+                Span::from("S(统计)"),
+                Span::from(" | "),
+                Span::from("G(分组)"),
             ]))
             .block(Block::bordered()),
             help_layout,
         );
     }
 
+    fn view_history(&self, frame: &mut Frame, area: Rect) {
+        let title = match self.storage {
+            Some(_) => "历史",
+            None => "历史 (未启用)",
+        };
+        let header = ["时间", "服务", "成功/响应", "平均耗时", "用时", "状态"];
+        let rows: Vec<_> = self
+            .history
+            .iter()
+            .map(|run| {
+                Row::new([
+                    Text::from(run.started().to_string()),
+                    Text::from(run.server().to_string()),
+                    Text::from(format!("{}/{}", run.success(), run.count())),
+                    Text::from(avg_total_text(run)),
+                    Text::from(format!("{:.3}", run.elapsed().as_secs_f64())),
+                    Text::from(state_text(run)),
+                ])
+            })
+            .collect();
+        let widths = [
+            cons_length(20),
+            cons_length(8),
+            cons_length(10),
+            cons_length(10),
+            cons_length(10),
+            cons_min(10),
+        ];
+        let table = Table::new(rows, widths)
+            .column_spacing(2)
+            .header(Row::new(header.map(|v| Text::from(v).bold())))
+            .block(
+                Block::bordered()
+                    .title(title)
+                    .title_alignment(Alignment::Center),
+            );
+
+        frame.render_widget(table, area);
+    }
+
+    /// Show the respone of selected task aggregated by the location of node.
+    fn view_group(&self, frame: &mut Frame, area: Rect, group_by: GroupBy) {
+        let respone = self
+            .task_index
+            .selected()
+            .and_then(|v| self.task_list.get(v))
+            .map(Task::respone)
+            .unwrap_or_default();
+        let fmt = |v: Option<Duration>| {
+            v.map(|v| format!("{:.3}", v.as_secs_f64()))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let header = [
+            group_by.name(),
+            "成功/响应",
+            "成功率",
+            "平均",
+            "中位数",
+            "P95",
+            "最大",
+        ];
+        let rows: Vec<_> = group_respone(respone, group_by)
+            .iter()
+            .map(|group| {
+                let summary = group.summary();
+                let row = Row::new([
+                    Text::from(group.name().to_string()),
+                    Text::from(format!("{}/{}", group.success(), group.count())),
+                    Text::from(format!("{:.1}%", group.success_ratio() * 100.0)),
+                    Text::from(fmt(summary.map(|v| v.avg()))),
+                    Text::from(fmt(summary.map(|v| v.median()))),
+                    Text::from(fmt(summary.map(|v| v.p95()))),
+                    Text::from(fmt(summary.map(|v| v.max()))),
+                ]);
+
+                // highlight the carrier or province which is partially or totally down
+                if group.success() == 0 {
+                    row.red()
+                } else if group.success() < group.count() {
+                    row.yellow()
+                } else {
+                    row
+                }
+            })
+            .collect();
+        let widths = [
+            cons_min(10),
+            cons_length(10),
+            cons_length(8),
+            cons_length(8),
+            cons_length(8),
+            cons_length(8),
+            cons_length(8),
+        ];
+        let table = Table::new(rows, widths)
+            .column_spacing(2)
+            .header(Row::new(header.map(|v| Text::from(v).bold())))
+            .block(
+                Block::bordered()
+                    .title(format!("按{}分组", group_by.name()))
+                    .title_alignment(Alignment::Center),
+            );
+
+        frame.render_widget(table, area);
+    }
+
+    /// Show the summary of phases, and the status classes of respone.
+    fn view_stats(&self, frame: &mut Frame, area: Rect, stats: &TaskStats) {
+        let block = Block::bordered()
+            .title(format!("统计 ({})", stats.count()))
+            .title_alignment(Alignment::Center);
+        let inner = block.inner(area);
+        let layout = Layout::vertical([cons_length(1), cons_min(1)]).split(inner);
+        let rows: Vec<_> = stats
+            .phases()
+            .iter()
+            .map(|phase| Row::new(summary_row(phase).into_iter().map(Text::from)))
+            .collect();
+        let mut widths = vec![cons_min(10)];
+
+        widths.extend([cons_length(8); SUMMARY_HEADER.len() - 1]);
+
+        let table = Table::new(rows, widths)
+            .column_spacing(1)
+            .header(Row::new(SUMMARY_HEADER.map(|v| Text::from(v).bold())));
+
+        frame.render_widget(block, area);
+        frame.render_widget(Paragraph::new(class_text(stats)), layout[0]);
+        frame.render_widget(table, layout[1]);
+    }
+
+    /// Show the median and p95 of total cost of each round, and the trend of selected node.
+    fn view_trend(&self, frame: &mut Frame, area: Rect) {
+        let Some(watch) = self.selected_watch() else {
+            return;
+        };
+        let rounds = watch.rounds();
+        let points = |f: &dyn Fn(&Round) -> Option<Duration>| -> Vec<(f64, f64)> {
+            rounds
+                .iter()
+                .enumerate()
+                .filter_map(|(i, round)| {
+                    f(round).map(|v| ((i + 1) as f64, v.as_secs_f64() * 1000.0))
+                })
+                .collect()
+        };
+        let median = points(&|round| round.median());
+        let p95 = points(&|round| round.p95());
+        // trend of the node selected in the table
+        let loc = match self.display_style {
+            DisplayStyle::Table => self
+                .task_index
+                .selected()
+                .and_then(|v| self.task_list.get(v))
+                .zip(self.table_index.selected())
+                .and_then(|(task, row)| task.respone().get(row))
+                .map(|resp| resp.loc().to_string()),
+            _ => None,
+        };
+        let node = loc
+            .as_deref()
+            .map(|loc| points(&|round| round.total_of(loc)))
+            .unwrap_or_default();
+        let x_max = rounds.len().max(2) as f64;
+        let y_max = median
+            .iter()
+            .chain(p95.iter())
+            .chain(node.iter())
+            .map(|(_, v)| *v)
+            .fold(1.0, f64::max)
+            * 1.1;
+        let mut datasets = vec![
+            Dataset::default()
+                .name("中位数")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::new().cyan())
+                .data(&median),
+            Dataset::default()
+                .name("P95")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::new().yellow())
+                .data(&p95),
+        ];
+
+        if let Some(loc) = loc {
+            datasets.push(
+                Dataset::default()
+                    .name(loc)
+                    .marker(symbols::Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::new().light_magenta())
+                    .data(&node),
+            );
+        }
+
+        let title = match watch.remaining() {
+            Some(remaining) => format!(
+                "趋势 第{}轮 (下一轮 {}秒)",
+                rounds.len(),
+                remaining.as_secs()
+            ),
+            None => format!("趋势 第{}轮 (运行中)", rounds.len() + 1),
+        };
+        let chart = Chart::new(datasets)
+            .block(
+                Block::bordered()
+                    .title(title)
+                    .title_alignment(Alignment::Center),
+            )
+            .x_axis(
+                Axis::default()
+                    .title("轮次")
+                    .bounds([1.0, x_max])
+                    .labels(vec![Span::from("1"), Span::from(format!("{}", x_max))]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("毫秒")
+                    .bounds([0.0, y_max])
+                    .labels(vec![Span::from("0"), Span::from(format!("{:.0}", y_max))]),
+            );
+
+        frame.render_widget(chart, area);
+    }
+
+    fn view_diff(&self, frame: &mut Frame, area: Rect) {
+        let current = self
+            .task_index
+            .selected()
+            .filter(|v| Some(*v) != self.baseline);
+        let tasks = self.baseline.zip(current).and_then(|(base, current)| {
+            Some((self.task_list.get(base)?, self.task_list.get(current)?))
+        });
+        // only the runs of same host are comparable, same as the diff subcommand
+        let message = match tasks {
+            None => Some("按 B 选择基准任务, 然后选择其他任务进行对比".to_string()),
+            Some((base, current)) if base.host() != current.host() => Some(format!(
+                "无法对比 {} 与 {} 的任务, 请选择相同地址的任务",
+                base.host(),
+                current.host()
+            )),
+            Some(_) => None,
+        };
+        let (Some((base, current)), None) = (tasks, message.as_ref()) else {
+            frame.render_widget(
+                Paragraph::new(message.unwrap_or_default())
+                    .centered()
+                    .block(
+                        Block::bordered()
+                            .title("对比")
+                            .title_alignment(Alignment::Center),
+                    ),
+                area,
+            );
+            return;
+        };
+        let comparison = Comparison::new(base.respone(), current.respone(), self.regression);
+        let header = [
+            "地址",
+            "基准状态",
+            "基准总耗时",
+            "当前状态",
+            "当前总耗时",
+            "变化",
+        ];
+        let fmt = |v: Option<&TaskRespone>| {
+            v.map(|v| (v.status().to_string(), v.total_cost().text().to_string()))
+                .unwrap_or_else(|| ("-".to_string(), "-".to_string()))
+        };
+        let rows: Vec<_> = comparison
+            .nodes()
+            .iter()
+            .map(|node| {
+                let (base_status, base_total) = fmt(node.base());
+                let (current_status, current_total) = fmt(node.current());
+                let changes = node
+                    .phases()
+                    .iter()
+                    .filter_map(|phase| {
+                        phase
+                            .percent()
+                            .map(|v| format!("{} {:+.1}%", phase.name(), v))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let row = Row::new([
+                    Text::from(node.loc().to_string()),
+                    Text::from(base_status),
+                    Text::from(base_total),
+                    Text::from(current_status),
+                    Text::from(current_total),
+                    Text::from(changes),
+                ]);
+
+                if node.regression() {
+                    row.red()
+                } else {
+                    row
+                }
+            })
+            .collect();
+        let widths = [
+            cons_length(12),
+            cons_length(8),
+            cons_length(10),
+            cons_length(8),
+            cons_length(10),
+            cons_min(10),
+        ];
+        let title = format!(
+            "对比 {} → {} (回归 {}/{})",
+            base.host(),
+            current.host(),
+            comparison.regressions().count(),
+            comparison.nodes().len()
+        );
+        let table = Table::new(rows, widths)
+            .column_spacing(1)
+            .header(Row::new(header.map(|v| Text::from(v).bold())))
+            .block(
+                Block::bordered()
+                    .title(title)
+                    .title_alignment(Alignment::Center),
+            );
+
+        frame.render_widget(table, area);
+    }
+
     pub fn update(&mut self, event: Event) -> color_eyre::Result<bool> {
         if let Event::Key(key) = event {
             if key.kind == KeyEventKind::Press {
@@ -407,6 +938,48 @@ impl App {
                         KeyCode::Char('e') => {
                             self.editing = true;
                         }
+                        KeyCode::Char('c') => {
+                            if let Some(task) = self
+                                .task_index
+                                .selected()
+                                .and_then(|v| self.task_list.get_mut(v))
+                            {
+                                task.cancell();
+                            }
+                        }
+                        KeyCode::Char('h') => {
+                            self.show_history = !self.show_history;
+                            self.show_diff = false;
+                            self.reload_history();
+                        }
+                        KeyCode::Char('b') => {
+                            let selected = self.task_index.selected();
+
+                            if self.baseline == selected {
+                                self.baseline = None;
+                            } else {
+                                self.baseline = selected.filter(|v| *v < self.task_list.len());
+                            }
+                        }
+                        KeyCode::Char('g') => {
+                            self.group_by = match self.group_by {
+                                None => Some(GroupBy::Isp),
+                                Some(GroupBy::Isp) => Some(GroupBy::Province),
+                                Some(GroupBy::Province) => None,
+                            };
+                        }
+                        KeyCode::Char('s') => {
+                            self.show_stats = !self.show_stats;
+                        }
+                        KeyCode::Char('w') => {
+                            if let Some(selected) = self.task_index.selected() {
+                                self.toggle_watch(selected);
+                            }
+                        }
+                        KeyCode::Char('d') => {
+                            self.show_diff = !self.show_diff;
+                            self.show_history = false;
+                        }
                         // change display style
                         KeyCode::Char('m') => match self.display_style {
                             DisplayStyle::Table => {
@@ -417,7 +990,11 @@ impl App {
                                 let mut changed = false;
 
                                 if let Some(selected) = self.task_index.selected() {
-                                    let resp = self.task_list[selected].respone();
+                                    let resp = self
+                                        .task_list
+                                        .get(selected)
+                                        .map(Task::respone)
+                                        .unwrap_or_default();
 
                                     if !resp.is_empty() && !resp[0].other_cost_list().is_empty() {
                                         self.display_style = DisplayStyle::Chart(0);
@@ -434,7 +1011,11 @@ impl App {
                                 let mut changed = false;
 
                                 if let Some(selected) = self.task_index.selected() {
-                                    let resp = self.task_list[selected].respone();
+                                    let resp = self
+                                        .task_list
+                                        .get(selected)
+                                        .map(Task::respone)
+                                        .unwrap_or_default();
 
                                     if !resp.is_empty() {
                                         let other_cost_len = resp[0].other_cost_list().len();
@@ -456,10 +1037,12 @@ impl App {
                         KeyCode::Down => {
                             self.task_index.select_next();
                             self.table_index = TableState::default();
+                            self.reload_history();
                         }
                         KeyCode::Up => {
                             self.task_index.select_previous();
                             self.table_index = TableState::default();
+                            self.reload_history();
                         }
                         KeyCode::Left => {
                             self.server_index.select_previous();
@@ -494,7 +1077,12 @@ impl App {
                         KeyCode::PageDown => match self.display_style {
                             DisplayStyle::Table => {
                                 if let Some(selected) = self.task_index.selected() {
-                                    let resp_len = self.task_list[selected].respone().len();
+                                    let resp_len = self
+                                        .task_list
+                                        .get(selected)
+                                        .map(Task::respone)
+                                        .unwrap_or_default()
+                                        .len();
 
                                     if self.table_index.offset() + 5 < resp_len {
                                         *self.table_index.offset_mut() =
@@ -505,7 +1093,12 @@ impl App {
                             }
                             DisplayStyle::Total => {
                                 if let Some(selected) = self.task_index.selected() {
-                                    let resp_len = self.task_list[selected].respone().len();
+                                    let resp_len = self
+                                        .task_list
+                                        .get(selected)
+                                        .map(Task::respone)
+                                        .unwrap_or_default()
+                                        .len();
 
                                     if self.total_index + 5 < resp_len {
                                         self.total_index += 5;
@@ -514,7 +1107,12 @@ impl App {
                             }
                             DisplayStyle::Chart(_) => {
                                 if let Some(selected) = self.task_index.selected() {
-                                    let resp_len = self.task_list[selected].respone().len();
+                                    let resp_len = self
+                                        .task_list
+                                        .get(selected)
+                                        .map(Task::respone)
+                                        .unwrap_or_default()
+                                        .len();
 
                                     if self.other_index + 5 < resp_len {
                                         self.other_index += 5;
@@ -531,7 +1129,18 @@ impl App {
                         }
                         KeyCode::Enter => {
                             if let Some(selected) = self.server_index.selected() {
-                                self.ping_host(selected, self.cache.clone());
+                                match self.cache.parse::<Target>() {
+                                    Ok(target) => {
+                                        self.ping_target(selected, &target);
+                                    }
+                                    Err(e) => {
+                                        let host = self.cache.clone();
+                                        let task =
+                                            Task::failed(self.runtime.handle(), host, e.into());
+
+                                        self.task_list.push(task);
+                                    }
+                                }
                                 self.cache.clear();
                                 self.editing = false;
                             }
@@ -548,8 +1157,33 @@ impl App {
     }
 
     pub fn handler<B: Write>(&mut self, _ui: &mut Ui<B>) -> color_eyre::Result<()> {
-        for task in self.task_list.iter_mut() {
+        let mut finished = false;
+
+        for (idx, task) in self.task_list.iter_mut().enumerate() {
             task.recv_respone();
+            if task.ending() && self.finished.insert(idx) {
+                if let Some(storage) = &self.storage {
+                    if let Err(e) = storage.save(task) {
+                        debug!("can not save history of `{}`: {}", task.host(), e);
+                    }
+                }
+                if let Some(watch) = self.watches.get_mut(&idx) {
+                    watch.finish_round(task);
+                }
+                finished = true;
+            }
+        }
+        // start next round of the watched task
+        for (idx, watch) in self.watches.iter_mut() {
+            if watch.is_due() {
+                if let Some(task) = self.task_list.get_mut(*idx) {
+                    *task = watch.spawn(self.runtime.handle());
+                    self.finished.remove(idx);
+                }
+            }
+        }
+        if finished {
+            self.reload_history();
         }
         Ok(())
     }
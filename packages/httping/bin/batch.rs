@@ -0,0 +1,263 @@
+use std::sync::Arc;
+
+use futures_util::future::join_all;
+use httping::class_text;
+use httping::junit_report;
+use httping::summary_row;
+use httping::PingServer;
+use httping::Storage;
+use httping::Task;
+use httping::TaskRespone;
+use httping::TaskStats;
+use httping::SUMMARY_HEADER;
+use itdog::csv_line;
+use itdog::OutputFormat;
+use itdog::Sample;
+use itdog::Thresholds;
+use itdog::EXIT_VIOLATION;
+use prettytable::Row;
+use prettytable::Table;
+use serde::Serialize;
+use tokio::runtime::Builder;
+
+/// A respone of host in the json, ndjson and csv output.
+#[derive(Debug, Serialize)]
+struct Record<'a> {
+    host: &'a str,
+
+    #[serde(flatten)]
+    respone: &'a TaskRespone,
+}
+
+/// A host pinged by the server, the respone are checked with the thresholds.
+pub struct Job {
+    server: Arc<dyn PingServer + Send + Sync>,
+
+    host: String,
+
+    thresholds: Thresholds,
+}
+
+impl Job {
+    pub fn new(
+        server: Arc<dyn PingServer + Send + Sync>,
+        host: String,
+        thresholds: Thresholds,
+    ) -> Self {
+        Self {
+            server,
+            host,
+            thresholds,
+        }
+    }
+}
+
+/// Run the jobs without tui, the hosts are pinged concurrently.
+///
+/// The respone of each host are printed in the order of jobs,
+/// except ndjson which is printed as soon as the respone arrived.
+/// The JUnit XML report is written and the tasks are saved to history if given.
+/// The process exit with [`EXIT_VIOLATION`] if any of the thresholds is violated.
+pub fn run(
+    jobs: Vec<Job>,
+    output: OutputFormat,
+    junit: Option<String>,
+    storage: Option<&Storage>,
+) -> color_eyre::Result<()> {
+    let runtime = Builder::new_multi_thread().enable_all().build()?;
+    let (mut tasks, thresholds): (Vec<_>, Vec<_>) = jobs
+        .into_iter()
+        .map(|job| {
+            let task = Task::spawn(runtime.handle(), job.server.as_ref(), job.host);
+
+            (task, job.thresholds)
+        })
+        .unzip();
+
+    match output {
+        OutputFormat::Table => {
+            for task in tasks.iter_mut() {
+                runtime.block_on(task.wait());
+                print_task(task);
+            }
+        }
+        OutputFormat::Ndjson => {
+            runtime.block_on(join_all(tasks.iter_mut().map(print_ndjson)));
+        }
+        OutputFormat::Json => {
+            runtime.block_on(join_all(tasks.iter_mut().map(Task::wait)));
+
+            let records: Vec<_> = tasks
+                .iter()
+                .flat_map(|task| {
+                    task.respone().iter().map(|respone| Record {
+                        host: task.host(),
+                        respone,
+                    })
+                })
+                .collect();
+
+            println!("{}", serde_json::to_string_pretty(&records)?);
+        }
+        OutputFormat::Csv => {
+            runtime.block_on(join_all(tasks.iter_mut().map(Task::wait)));
+            print_csv(&tasks);
+        }
+    }
+    if output != OutputFormat::Table {
+        for task in tasks.iter() {
+            print_errors(task);
+        }
+    }
+    if let Some(storage) = storage {
+        for task in tasks.iter() {
+            if let Err(e) = storage.save(task) {
+                eprintln!("错误 {}: can not save history: {}", task.host(), e);
+            }
+        }
+    }
+    if let Some(path) = junit {
+        std::fs::write(path, junit_report(&tasks, &thresholds)?)?;
+    }
+
+    let mut violations = vec![];
+
+    for (task, thresholds) in tasks.iter().zip(thresholds.iter()) {
+        let samples: Vec<_> = task
+            .respone()
+            .iter()
+            .map(|resp| Sample::new(resp.loc(), resp.status(), resp.total_cost().duration()))
+            .collect();
+
+        violations.extend(
+            thresholds
+                .check(&samples)
+                .into_iter()
+                .map(|v| (task.host(), v)),
+        );
+    }
+
+    let failed = tasks.iter().filter(|v| !v.errors().is_empty()).count();
+
+    if !violations.is_empty() {
+        eprintln!("{} threshold violations:", violations.len());
+        for (host, violation) in violations.iter() {
+            eprintln!("  {}: {}", host, violation);
+        }
+    }
+    if failed > 0 {
+        return Err(color_eyre::eyre::eyre!(
+            "{} of {} tasks failed",
+            failed,
+            tasks.len()
+        ));
+    }
+    if !violations.is_empty() {
+        std::process::exit(EXIT_VIOLATION);
+    }
+    Ok(())
+}
+
+async fn print_ndjson(task: &mut Task) {
+    let host = task.host().to_string();
+
+    while let Some(respone) = task.recv().await {
+        let record = Record {
+            host: &host,
+            respone,
+        };
+
+        match serde_json::to_string(&record) {
+            Ok(line) => println!("{}", line),
+            Err(e) => eprintln!("错误 {}: {}", host, e),
+        }
+    }
+}
+
+fn print_csv(tasks: &[Task]) {
+    let mut header = [
+        "host",
+        "loc",
+        "ip",
+        "status",
+        "total_cost",
+        "redirect",
+        "redirect_cost",
+    ]
+    .map(String::from)
+    .to_vec();
+
+    if let Some(resp) = tasks.iter().find_map(|v| v.respone().first()) {
+        header.extend(resp.other_name_list().iter().cloned());
+        header.extend(resp.other_info().iter().map(|(name, _)| name.clone()));
+    }
+    println!("{}", csv_line(header));
+    for task in tasks {
+        for resp in task.respone() {
+            let mut row = vec![task.host().to_string()];
+
+            row.extend(respone_row(resp));
+            println!("{}", csv_line(row));
+        }
+    }
+}
+
+fn respone_row(resp: &TaskRespone) -> Vec<String> {
+    let mut row = vec![
+        resp.loc().to_string(),
+        resp.ip().to_string(),
+        resp.status().to_string(),
+        resp.total_cost().to_string(),
+        resp.redirect().to_string(),
+        resp.redirect_cost().to_string(),
+    ];
+
+    row.extend(resp.other_cost_list().iter().map(ToString::to_string));
+    row.extend(resp.other_info().iter().map(|(_, value)| value.clone()));
+    row
+}
+
+fn print_task(task: &Task) {
+    println!("{} ({})", task.host(), task.respone().len());
+    print_respone(task.respone());
+    print_stats(task.respone());
+    print_errors(task);
+}
+
+pub fn print_respone(respone: &[TaskRespone]) {
+    if !respone.is_empty() {
+        let mut table = Table::new();
+        let mut header = ["地址", "IP", "状态", "总耗时", "重定向", "重定向耗时"]
+            .map(String::from)
+            .to_vec();
+
+        header.extend(respone[0].other_name_list().iter().cloned());
+        header.extend(respone[0].other_info().iter().map(|(name, _)| name.clone()));
+        table.add_row(Row::from_iter(header));
+        for resp in respone {
+            table.add_row(Row::from_iter(respone_row(resp)));
+        }
+        table.printstd();
+    }
+}
+
+/// Print the summary of phases and the status classes of the respone.
+pub fn print_stats(respone: &[TaskRespone]) {
+    if !respone.is_empty() {
+        let stats = TaskStats::new(respone);
+        let mut table = Table::new();
+
+        table.add_row(Row::from_iter(SUMMARY_HEADER));
+        for phase in stats.phases() {
+            table.add_row(Row::from_iter(summary_row(phase)));
+        }
+        table.printstd();
+        println!("{}", class_text(&stats));
+    }
+}
+
+fn print_errors(task: &Task) {
+    for error in task.errors() {
+        eprintln!("错误 {}: {}", task.host(), error);
+    }
+}
@@ -0,0 +1,101 @@
+use httping::phase_text;
+use httping::status_text;
+use httping::Comparison;
+use httping::Storage;
+use itdog::EXIT_VIOLATION;
+use prettytable::Cell;
+use prettytable::Row;
+use prettytable::Table;
+
+/// Compare the two runs, the latest two runs of host are used if the ids are not given.
+///
+/// The process exit with [`EXIT_VIOLATION`] if any regression is found.
+pub fn show(
+    storage: &Storage,
+    base: Option<i64>,
+    current: Option<i64>,
+    host: Option<&str>,
+    threshold: f64,
+) -> color_eyre::Result<()> {
+    let (base, current) = match (base, current, host) {
+        (Some(base), Some(current), _) => (base, current),
+        (None, None, Some(host)) => {
+            let runs = storage.runs(Some(host), 2)?;
+
+            if runs.len() < 2 {
+                return Err(color_eyre::eyre::eyre!(
+                    "need two runs of `{}` to compare, found {}",
+                    host,
+                    runs.len()
+                ));
+            }
+            (runs[1].id(), runs[0].id())
+        }
+        _ => {
+            return Err(color_eyre::eyre::eyre!(
+                "set the base and current run id, or the host"
+            ))
+        }
+    };
+    let find_run = |id| {
+        storage
+            .run(id)?
+            .ok_or_else(|| color_eyre::eyre::eyre!("can not find run {}", id))
+    };
+    let (base_run, current_run) = (find_run(base)?, find_run(current)?);
+
+    if base_run.host() != current_run.host() {
+        return Err(color_eyre::eyre::eyre!(
+            "can not compare run of `{}` with run of `{}`",
+            base_run.host(),
+            current_run.host()
+        ));
+    }
+
+    let comparison = Comparison::new(
+        &storage.respone(base)?,
+        &storage.respone(current)?,
+        threshold,
+    );
+    let mut table = Table::new();
+    let mut header = vec!["地址".to_string(), "状态".to_string()];
+
+    header.extend(comparison.phase_names());
+    table.add_row(Row::from_iter(header));
+    for node in comparison.nodes() {
+        let style = if node.regression() { "Fr" } else { "" };
+        let mut cells = vec![
+            Cell::new(node.loc()).style_spec(style),
+            Cell::new(&status_text(node)).style_spec(style),
+        ];
+
+        cells.extend(
+            node.phases()
+                .iter()
+                .map(|phase| Cell::new(&phase_text(phase)).style_spec(style)),
+        );
+        table.add_row(Row::new(cells));
+    }
+    println!(
+        "{} {} → {} ({} → {})",
+        base_run.host(),
+        base,
+        current,
+        base_run.started(),
+        current_run.started()
+    );
+    table.printstd();
+
+    let regressions = comparison.regressions().count();
+
+    if regressions > 0 {
+        eprintln!(
+            "{} of {} nodes regressed beyond {}%",
+            regressions,
+            comparison.nodes().len(),
+            threshold
+        );
+        std::process::exit(EXIT_VIOLATION);
+    }
+    Ok(())
+}
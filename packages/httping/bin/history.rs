@@ -0,0 +1,86 @@
+use httping::RunRecord;
+use httping::Storage;
+use prettytable::Row;
+use prettytable::Table;
+
+use crate::batch::print_respone;
+use crate::batch::print_stats;
+
+/// Format the average cost of successful respone, `-` if there is no successful respone.
+pub fn avg_total_text(run: &RunRecord) -> String {
+    run.avg_total()
+        .map(|v| format!("{:.3}", v.as_secs_f64()))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// The state of run, such as `已取消` or the first error.
+pub fn state_text(run: &RunRecord) -> String {
+    if run.cancelled() {
+        "已取消".to_string()
+    } else if let Some(error) = run.errors().first() {
+        format!("失败: {}", error)
+    } else {
+        "完成".to_string()
+    }
+}
+
+/// Print the latest runs, or the respone of the run if `id` is given.
+pub fn show(
+    storage: &Storage,
+    host: Option<&str>,
+    id: Option<i64>,
+    limit: usize,
+) -> color_eyre::Result<()> {
+    if let Some(id) = id {
+        let run = storage
+            .run(id)?
+            .ok_or_else(|| color_eyre::eyre::eyre!("can not find run {}", id))?;
+
+        println!(
+            "{} {} {} ({}, {:.3}s)",
+            run.id(),
+            run.host(),
+            run.server(),
+            run.started(),
+            run.elapsed().as_secs_f64()
+        );
+        if !run.options().is_empty() {
+            println!("{}", run.options());
+        }
+        let respone = storage.respone(id)?;
+
+        print_respone(&respone);
+        print_stats(&respone);
+        for error in run.errors() {
+            eprintln!("错误 {}: {}", run.host(), error);
+        }
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+
+    table.add_row(Row::from_iter([
+        "ID",
+        "时间",
+        "主机",
+        "服务",
+        "成功/响应",
+        "平均耗时",
+        "用时",
+        "状态",
+    ]));
+    for run in storage.runs(host, limit)? {
+        table.add_row(Row::from_iter([
+            run.id().to_string(),
+            run.started().to_string(),
+            run.host().to_string(),
+            run.server().to_string(),
+            format!("{}/{}", run.success(), run.count()),
+            avg_total_text(&run),
+            format!("{:.3}", run.elapsed().as_secs_f64()),
+            state_text(&run),
+        ]));
+    }
+    table.printstd();
+    Ok(())
+}
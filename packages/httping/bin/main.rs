@@ -1,11 +1,37 @@
 use std::io::stdout;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 mod app;
+mod batch;
+mod diff;
+mod history;
 
 use app::App;
+use batch::Job;
 use cote::prelude::*;
+use httping::find_server;
+use httping::Dns;
 use httping::Itdog;
+use httping::LocalHttp;
+use httping::PingServer;
+use httping::Storage;
+use httping::Tcping;
+use httping::Tls;
 use httping::Ui;
+use itdog::parse_duration;
+use itdog::parse_status_list;
+use itdog::read_targets;
+use itdog::Endpoint;
+use itdog::HttpMethod;
+use itdog::ItdogClient;
+use itdog::QueryOptions;
+use itdog::RetryPolicy;
+use itdog::Target;
+use itdog::Thresholds;
+use itdog::Timeouts;
+use itdog::DEFAULT_HTTP_URL;
+use itdog::DEFAULT_WS_URL;
 use tracing_subscriber::filter::LevelFilter;
 
 #[derive(Debug, Cote)]
@@ -22,6 +48,201 @@ struct Cli {
     /// Log the debug message to the file
     #[arg(alias = "-l")]
     pub log: Option<String>,
+
+    /// Set the line of itdog nodes: all, telecom, unicom, mobile or overseas
+    #[arg(value = "all")]
+    pub line: String,
+
+    /// Set the check mode of itdog: fast or slow
+    #[arg(alias = "-m", value = "fast")]
+    pub mode: String,
+
+    /// Set the ip address of host for itdog, empty means resolve by node
+    #[arg(value = "")]
+    pub ipv4: String,
+
+    /// Set the http method: get, post or head
+    #[arg(alias = "-X", value = "get")]
+    pub method: String,
+
+    /// Set the referer of itdog request
+    #[arg(value = "")]
+    pub referer: String,
+
+    /// Set the user agent of request
+    #[arg(alias = "-u", value = "")]
+    pub useragent: String,
+
+    /// Set the cookies of itdog request
+    #[arg(value = "")]
+    pub cookies: String,
+
+    /// Set the maximum redirect count of itdog request
+    #[arg(value = 5i32)]
+    pub redirect: i32,
+
+    /// Set the dns server type of itdog request: isp or custom
+    #[arg(value = "isp")]
+    pub dns_type: String,
+
+    /// Set the dns server of itdog request, using with custom dns server type
+    #[arg(value = "")]
+    pub dns_server: String,
+
+    /// Set the http url of itdog
+    #[arg(value = DEFAULT_HTTP_URL)]
+    pub http_url: String,
+
+    /// Set the websocket url of itdog, tls is enabled for `wss` scheme
+    #[arg(value = DEFAULT_WS_URL)]
+    pub ws_url: String,
+
+    /// Trust the certificates in the PEM file when connecting itdog
+    pub ca_cert: Option<String>,
+
+    /// Set the timeout of connecting itdog, such as 500ms, 10s or 0 for no limit
+    #[arg(value = "10s")]
+    pub connect_timeout: String,
+
+    /// Set the timeout of itdog websocket handshake
+    #[arg(value = "10s")]
+    pub handshake_timeout: String,
+
+    /// Set the timeout of waiting the first message of itdog
+    #[arg(value = "30s")]
+    pub first_message_timeout: String,
+
+    /// Set the timeout of whole itdog query, including the retries
+    #[arg(alias = "-t", value = "120s")]
+    pub timeout: String,
+
+    /// Set the maximum retry count of itdog query when failed with transient error
+    #[arg(value = 2usize)]
+    pub retry: usize,
+
+    /// Set the delay before first retry, it is doubled for each retry
+    #[arg(value = "500ms")]
+    pub retry_backoff: String,
+
+    /// Read the targets from the file or stdin if it is `-`, one per line
+    /// with optional overrides, such as `www.baidu.com method=head server=tcping`
+    #[arg(alias = "-f")]
+    pub file: Option<String>,
+
+    /// Set the history database, default is `$HOME/.httping/history.db`
+    pub db: Option<String>,
+
+    /// Do not save the runs to history
+    pub no_history: bool,
+
+    /// Set the percent of slower than baseline treated as regression in the diff view
+    #[arg(value = 20f64)]
+    pub regression: f64,
+
+    /// Set the interval of watched tasks, press `w` to watch the selected task
+    #[arg(value = "5m")]
+    pub interval: String,
+
+    /// Watch the targets read from the file
+    #[arg(alias = "-w")]
+    pub watch: bool,
+
+    /// Ping the hosts without tui and print the results
+    #[sub(force = false)]
+    pub run: Option<Run>,
+
+    /// Show the runs saved in history
+    #[sub(force = false)]
+    pub history: Option<History>,
+
+    /// Compare two runs saved in history node by node
+    #[sub(force = false)]
+    pub diff: Option<Diff>,
+}
+
+#[derive(Debug, Cote)]
+#[cote(help, aborthelp)]
+struct Run {
+    /// Set the server used to ping the hosts: itdog, local, tcping, dns or tls
+    #[arg(alias = "-s", value = "itdog")]
+    pub server: String,
+
+    /// Set the output format: table, json, ndjson or csv
+    #[arg(alias = "-o", value = "table")]
+    pub output: String,
+
+    /// Fail if the total cost of any respone exceeds the duration, such as 2s
+    #[arg(value = "0")]
+    pub max_total: String,
+
+    /// Fail if the ratio of successful respone is below the value, such as 0.95
+    pub min_success_ratio: Option<f64>,
+
+    /// Set the expected status such as 200,301, fail if any respone has other status
+    #[arg(value = "")]
+    pub expect_status: String,
+
+    /// Fail if the p95 of total cost of successful respone exceeds the duration
+    #[arg(value = "0")]
+    pub max_p95: String,
+
+    /// Write the JUnit XML report to the file
+    pub junit: Option<String>,
+
+    /// Read the targets from the file or stdin if it is `-`, one per line
+    /// with optional overrides, such as `www.baidu.com method=head status=200,301 server=tcping`
+    #[arg(alias = "-f")]
+    pub file: Option<String>,
+
+    /// The target hosts, for example: www.baidu.com
+    #[pos(index = 1..)]
+    pub hosts: Option<Vec<String>>,
+}
+
+#[derive(Debug, Cote)]
+#[cote(help, aborthelp)]
+struct History {
+    /// Show the respone of the run
+    #[arg(alias = "-i")]
+    pub id: Option<i64>,
+
+    /// Set the maximum count of runs
+    #[arg(alias = "-n", value = 20usize)]
+    pub limit: usize,
+
+    /// Show the runs of the host only
+    #[pos()]
+    pub host: Option<String>,
+}
+
+#[derive(Debug, Cote)]
+#[cote(help, aborthelp)]
+struct Diff {
+    /// Compare the latest two runs of the host if the run ids are not given
+    #[arg(alias = "-H")]
+    pub host: Option<String>,
+
+    /// Set the percent of slower than base treated as regression
+    #[arg(alias = "-t", value = 20f64)]
+    pub threshold: f64,
+
+    /// The id of base run
+    #[pos(index = 1)]
+    pub base: Option<i64>,
+
+    /// The id of current run
+    #[pos(index = 2)]
+    pub current: Option<i64>,
+}
+
+/// Open the history database, the directory of it is created if not exist.
+fn open_storage(db: Option<String>) -> color_eyre::Result<Storage> {
+    let path = db.map(PathBuf::from).unwrap_or_else(Storage::default_path);
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    Ok(Storage::open(path)?)
 }
 
 fn main() -> color_eyre::Result<()> {
@@ -30,6 +251,34 @@ fn main() -> color_eyre::Result<()> {
         debug,
         verbose,
         log,
+        line,
+        mode,
+        ipv4,
+        method,
+        referer,
+        useragent,
+        cookies,
+        redirect,
+        dns_type,
+        dns_server,
+        http_url,
+        ws_url,
+        ca_cert,
+        connect_timeout,
+        handshake_timeout,
+        first_message_timeout,
+        timeout,
+        retry,
+        retry_backoff,
+        file,
+        db,
+        no_history,
+        regression,
+        interval,
+        watch,
+        run,
+        history,
+        diff,
     } = Cli::parse_env()?;
 
     if let Some(path) = log {
@@ -74,8 +323,158 @@ fn main() -> color_eyre::Result<()> {
         }
     }
 
+    let method: HttpMethod = method.parse()?;
+    let options = QueryOptions::default()
+        .with_line(line.parse()?)
+        .with_mode(mode.parse()?)
+        .with_ipv4(ipv4)
+        .with_method(method)
+        .with_referer(referer)
+        .with_useragent(useragent.clone())
+        .with_cookies(cookies)
+        .with_redirect(redirect)
+        .with_dns_type(dns_type.parse()?)
+        .with_dns_server(dns_server);
+    let mut local = LocalHttp::default().with_method(method.into());
+
+    options.validate()?;
+
+    if !useragent.is_empty() {
+        local = local.with_useragent(useragent);
+    }
+
+    let mut endpoint = Endpoint::new(http_url, ws_url);
+
+    if let Some(path) = ca_cert {
+        endpoint = endpoint.with_root_certs_pem(path)?;
+    }
+
+    let timeouts = Timeouts::default()
+        .with_connect(parse_duration(&connect_timeout)?)
+        .with_handshake(parse_duration(&handshake_timeout)?)
+        .with_first_message(parse_duration(&first_message_timeout)?)
+        .with_overall(parse_duration(&timeout)?);
+    let retry = RetryPolicy::default()
+        .with_max_retries(retry)
+        .with_initial_backoff(parse_duration(&retry_backoff)?.unwrap_or_default());
+    let client = ItdogClient::builder()
+        .with_endpoint(endpoint)
+        .with_timeouts(timeouts)
+        .with_retry(retry)
+        .build()?;
+    let servers: Vec<Arc<dyn PingServer + Send + Sync>> = vec![
+        Arc::new(Itdog::new(client).with_options(options)),
+        Arc::new(local),
+        Arc::new(Tcping::default()),
+        Arc::new(Dns::default()),
+        Arc::new(Tls::default()),
+    ];
+
+    if let Some(History { id, limit, host }) = history {
+        return history::show(&open_storage(db)?, host.as_deref(), id, limit);
+    }
+    if let Some(Diff {
+        host,
+        threshold,
+        base,
+        current,
+    }) = diff
+    {
+        return diff::show(
+            &open_storage(db)?,
+            base,
+            current,
+            host.as_deref(),
+            threshold,
+        );
+    }
+    if let Some(Run {
+        server,
+        output,
+        max_total,
+        min_success_ratio,
+        expect_status,
+        max_p95,
+        junit,
+        file: run_file,
+        hosts,
+    }) = run
+    {
+        let thresholds = Thresholds::default()
+            .with_max_total(parse_duration(&max_total)?)
+            .with_min_success_ratio(min_success_ratio)
+            .with_expect_status(parse_status_list(&expect_status)?)
+            .with_max_p95(parse_duration(&max_p95)?);
+
+        thresholds.validate()?;
+
+        let mut targets: Vec<_> = hosts
+            .unwrap_or_default()
+            .into_iter()
+            .map(Target::new)
+            .collect();
+
+        // the `-f` before the `run` is parsed by the top level
+        if let Some(path) = run_file.or(file) {
+            targets.extend(read_targets(&path)?);
+        }
+        if targets.is_empty() {
+            return Err(color_eyre::eyre::eyre!(
+                "no host given, set the hosts or the targets file"
+            ));
+        }
+
+        let mut jobs = vec![];
+
+        for target in targets {
+            let name = target.server().unwrap_or(&server);
+            let server = find_server(&servers, name, target.method())?;
+            let mut thresholds = thresholds.clone();
+
+            if !target.expect_status().is_empty() {
+                thresholds = thresholds.with_expect_status(target.expect_status().to_vec());
+            }
+            jobs.push(Job::new(server, target.host().to_string(), thresholds));
+        }
+
+        let storage = if no_history {
+            None
+        } else {
+            Some(open_storage(db)?)
+        };
+
+        return batch::run(jobs, output.parse()?, junit, storage.as_ref());
+    }
+
+    let interval = parse_duration(&interval)?
+        .ok_or_else(|| color_eyre::eyre::eyre!("the interval of watch can not be zero"))?;
+    let targets = match file {
+        Some(path) => read_targets(&path)?,
+        None => vec![],
+    };
+
+    let storage = if no_history {
+        None
+    } else {
+        Some(open_storage(db)?)
+    };
     let mut ui = Ui::new(stdout())?;
-    let mut app = App::default().with_server(Itdog);
+    let mut app = App::default()
+        .with_servers(servers)
+        .with_regression(regression)
+        .with_interval(interval);
+
+    if let Some(storage) = storage {
+        app = app.with_storage(storage);
+    }
+
+    for target in targets.iter() {
+        if watch {
+            app.watch_target(0, target);
+        } else {
+            app.ping_target(0, target);
+        }
+    }
 
     ui.run_loop(&mut app, App::view, App::update, App::handler)?;
 
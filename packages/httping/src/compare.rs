@@ -0,0 +1,246 @@
+use std::time::Duration;
+
+use crate::TaskRespone;
+
+/// Name of the total cost phase in the comparison.
+pub const TOTAL_PHASE: &str = "总耗时";
+
+fn is_success(status: i32) -> bool {
+    (200..300).contains(&status)
+}
+
+/// The key of matching nodes, the location such as `#1 127.0.0.1:51234` of tcping
+/// is matched by the attempt since the rest changes between runs.
+pub fn node_key(loc: &str) -> &str {
+    match loc.split_once(' ') {
+        Some((attempt, _)) if attempt.starts_with('#') => attempt,
+        _ => loc,
+    }
+}
+
+/// The timing of one phase in the base and current run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseDiff {
+    name: String,
+
+    base: Option<Duration>,
+
+    current: Option<Duration>,
+}
+
+impl PhaseDiff {
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn base(&self) -> Option<Duration> {
+        self.base
+    }
+
+    pub fn current(&self) -> Option<Duration> {
+        self.current
+    }
+
+    /// The change in percent of the base, `None` if any of them is missing or base is zero.
+    pub fn percent(&self) -> Option<f64> {
+        let base = self.base?.as_secs_f64();
+        let current = self.current?.as_secs_f64();
+
+        (base > 0.0).then(|| (current - base) / base * 100.0)
+    }
+
+    /// Return true if the phase is slower than base beyond the threshold percent.
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.percent().is_some_and(|v| v > threshold)
+    }
+}
+
+/// The difference of a node matched by [`node_key`].
+#[derive(Debug, Clone)]
+pub struct NodeDiff {
+    loc: String,
+
+    base: Option<TaskRespone>,
+
+    current: Option<TaskRespone>,
+
+    phases: Vec<PhaseDiff>,
+
+    regression: bool,
+}
+
+impl NodeDiff {
+    fn new(
+        loc: String,
+        base: Option<&TaskRespone>,
+        current: Option<&TaskRespone>,
+        threshold: f64,
+    ) -> Self {
+        let mut phases = vec![PhaseDiff {
+            name: TOTAL_PHASE.to_string(),
+            base: base.and_then(|v| v.total_cost().duration()),
+            current: current.and_then(|v| v.total_cost().duration()),
+        }];
+        let names = base
+            .or(current)
+            .map(TaskRespone::other_name_list)
+            .unwrap_or_default();
+
+        for name in names {
+            let phase_of = |resp: Option<&TaskRespone>| {
+                resp.and_then(|resp| {
+                    let idx = resp.other_name_list().iter().position(|v| v == name)?;
+
+                    resp.other_cost_list().get(idx)?.duration()
+                })
+            };
+
+            phases.push(PhaseDiff {
+                name: name.clone(),
+                base: phase_of(base),
+                current: phase_of(current),
+            });
+        }
+
+        let status_regression = match (base, current) {
+            (Some(base), Some(current)) => {
+                is_success(base.status()) && !is_success(current.status())
+            }
+            // the node is missing in current run
+            (Some(_), None) => true,
+            _ => false,
+        };
+        let regression =
+            status_regression || phases.iter().any(|phase| phase.is_regression(threshold));
+
+        Self {
+            loc,
+            base: base.cloned(),
+            current: current.cloned(),
+            phases,
+            regression,
+        }
+    }
+
+    pub fn loc(&self) -> &str {
+        self.loc.as_str()
+    }
+
+    pub fn base(&self) -> Option<&TaskRespone> {
+        self.base.as_ref()
+    }
+
+    pub fn current(&self) -> Option<&TaskRespone> {
+        self.current.as_ref()
+    }
+
+    pub fn base_status(&self) -> Option<i32> {
+        self.base.as_ref().map(TaskRespone::status)
+    }
+
+    pub fn current_status(&self) -> Option<i32> {
+        self.current.as_ref().map(TaskRespone::status)
+    }
+
+    /// The total cost followed by other phases.
+    pub fn phases(&self) -> &[PhaseDiff] {
+        self.phases.as_slice()
+    }
+
+    /// Return true if the status is failed, the node is missing or any phase is slower.
+    pub fn regression(&self) -> bool {
+        self.regression
+    }
+}
+
+/// The comparison of two runs of same host, the nodes are matched by [`node_key`].
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    threshold: f64,
+
+    nodes: Vec<NodeDiff>,
+}
+
+impl Comparison {
+    /// Compare the respone of two runs, the phase slower than base beyond `threshold` percent
+    /// is a regression.
+    pub fn new(base: &[TaskRespone], current: &[TaskRespone], threshold: f64) -> Self {
+        let mut nodes: Vec<_> = base
+            .iter()
+            .map(|resp| {
+                let key = node_key(resp.loc());
+                let matched = current.iter().find(|v| node_key(v.loc()) == key);
+
+                NodeDiff::new(key.to_string(), Some(resp), matched, threshold)
+            })
+            .collect();
+
+        nodes.extend(
+            current
+                .iter()
+                .filter(|resp| {
+                    let key = node_key(resp.loc());
+
+                    !base.iter().any(|v| node_key(v.loc()) == key)
+                })
+                .map(|resp| {
+                    NodeDiff::new(
+                        node_key(resp.loc()).to_string(),
+                        None,
+                        Some(resp),
+                        threshold,
+                    )
+                }),
+        );
+        Self { threshold, nodes }
+    }
+
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    pub fn nodes(&self) -> &[NodeDiff] {
+        self.nodes.as_slice()
+    }
+
+    /// The name of phases, the total cost is the first one.
+    pub fn phase_names(&self) -> Vec<String> {
+        self.nodes
+            .first()
+            .map(|node| node.phases.iter().map(|v| v.name.clone()).collect())
+            .unwrap_or_else(|| vec![TOTAL_PHASE.to_string()])
+    }
+
+    pub fn regressions(&self) -> impl Iterator<Item = &NodeDiff> {
+        self.nodes.iter().filter(|v| v.regression)
+    }
+}
+
+/// Format the phase such as `0.100 → 0.150 (+50.0%)`.
+pub fn phase_text(phase: &PhaseDiff) -> String {
+    let fmt = |v: Option<Duration>| {
+        v.map(|v| format!("{:.3}", v.as_secs_f64()))
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    match phase.percent() {
+        Some(percent) => format!(
+            "{} → {} ({:+.1}%)",
+            fmt(phase.base),
+            fmt(phase.current),
+            percent
+        ),
+        None => format!("{} → {}", fmt(phase.base), fmt(phase.current)),
+    }
+}
+
+/// Format the status such as `200 → 502`.
+pub fn status_text(node: &NodeDiff) -> String {
+    let fmt = |v: Option<i32>| v.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string());
+
+    format!(
+        "{} → {}",
+        fmt(node.base_status()),
+        fmt(node.current_status())
+    )
+}
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use itdog::Location;
+
+use crate::stats::StatusClass;
+use crate::stats::Summary;
+use crate::TaskRespone;
+
+/// Name of the group which the location can not be recognized.
+pub const UNKNOWN_GROUP: &str = "未知";
+
+/// The key of grouping the respone by location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GroupBy {
+    Isp,
+    Province,
+}
+
+impl GroupBy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GroupBy::Isp => "运营商",
+            GroupBy::Province => "省份",
+        }
+    }
+
+    /// The key of location, `None` if the location can not be recognized.
+    pub fn key_of<'a>(&self, location: &'a Location) -> Option<&'a str> {
+        match self {
+            GroupBy::Isp => location.isp(),
+            GroupBy::Province => location.province().or(location.country()),
+        }
+    }
+}
+
+/// The aggregated respone of nodes in same group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    name: String,
+
+    count: usize,
+
+    success: usize,
+
+    summary: Option<Summary>,
+}
+
+impl Group {
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Count of respone.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Count of respone with 2xx status.
+    pub fn success(&self) -> usize {
+        self.success
+    }
+
+    /// Ratio of successful respone.
+    pub fn success_ratio(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.success as f64 / self.count as f64
+        }
+    }
+
+    /// The summary of total cost of successful respone.
+    pub fn summary(&self) -> Option<&Summary> {
+        self.summary.as_ref()
+    }
+}
+
+/// Group the respone by location, the groups are in the order they first appear.
+pub fn group_respone(respone: &[TaskRespone], by: GroupBy) -> Vec<Group> {
+    let mut groups: Vec<(String, usize, usize, Vec<Duration>)> = vec![];
+
+    for resp in respone {
+        let location = Location::parse(resp.loc());
+        let name = by.key_of(&location).unwrap_or(UNKNOWN_GROUP);
+        let idx = match groups.iter().position(|(v, ..)| v == name) {
+            Some(idx) => idx,
+            None => {
+                groups.push((name.to_string(), 0, 0, vec![]));
+                groups.len() - 1
+            }
+        };
+        let (_, count, success, totals) = &mut groups[idx];
+
+        *count += 1;
+        if StatusClass::of(resp.status()) == StatusClass::Success {
+            *success += 1;
+            totals.extend(resp.total_cost().duration());
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(name, count, success, totals)| Group {
+            name,
+            count,
+            success,
+            summary: Summary::new(totals),
+        })
+        .collect()
+}
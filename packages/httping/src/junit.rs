@@ -0,0 +1,159 @@
+use std::fmt::Write;
+
+use itdog::Thresholds;
+use itdog::Violation;
+
+use crate::Task;
+use crate::TaskRespone;
+
+/// Generate the JUnit XML report, each host is a test suite and each respone is a test case.
+///
+/// The respone of each task are checked with the thresholds in same order,
+/// the errors of task are reported as errored test case.
+pub fn junit_report(tasks: &[Task], thresholds: &[Thresholds]) -> Result<String, std::fmt::Error> {
+    let mut suites = String::new();
+    let (mut tests, mut failures, mut errors, mut time) = (0, 0, 0, 0.0);
+
+    for (task, thresholds) in tasks.iter().zip(thresholds.iter()) {
+        let (suite, suite_failures, suite_time) = test_suite(task, thresholds)?;
+
+        tests += task.respone().len() + task.errors().len();
+        failures += suite_failures;
+        errors += task.errors().len();
+        time += suite_time;
+        suites.push_str(&suite);
+    }
+
+    let mut xml = String::new();
+
+    writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        xml,
+        r#"<testsuites name="httping" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+        tests, failures, errors, time
+    )?;
+    xml.push_str(&suites);
+    writeln!(xml, "</testsuites>")?;
+    Ok(xml)
+}
+
+fn test_suite(
+    task: &Task,
+    thresholds: &Thresholds,
+) -> Result<(String, usize, f64), std::fmt::Error> {
+    let host = escape(task.host());
+    let mut cases = String::new();
+    let (mut failures, mut time) = (0, 0.0);
+
+    for resp in task.respone() {
+        let secs = resp
+            .total_cost()
+            .duration()
+            .map(|v| v.as_secs_f64())
+            .unwrap_or_default();
+        let violations = check_case(thresholds, resp);
+
+        time += secs;
+        write!(
+            cases,
+            r#"    <testcase name="{}" classname="{}" time="{:.3}""#,
+            escape(resp.loc()),
+            host,
+            secs
+        )?;
+        if violations.is_empty() {
+            writeln!(cases, "/>")?;
+        } else {
+            let message = violations
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ");
+
+            failures += 1;
+            writeln!(cases, ">")?;
+            writeln!(
+                cases,
+                r#"      <failure message="{}" type="threshold">ip: {}, status: {}, total cost: {}</failure>"#,
+                escape(&message),
+                escape(resp.ip()),
+                resp.status(),
+                escape(resp.total_cost().text())
+            )?;
+            writeln!(cases, "    </testcase>")?;
+        }
+    }
+    for error in task.errors() {
+        writeln!(cases, r#"    <testcase name="task" classname="{}">"#, host)?;
+        writeln!(
+            cases,
+            r#"      <error message="{}" type="error"/>"#,
+            escape(error)
+        )?;
+        writeln!(cases, "    </testcase>")?;
+    }
+
+    let mut suite = String::new();
+
+    writeln!(
+        suite,
+        r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+        host,
+        task.respone().len() + task.errors().len(),
+        failures,
+        task.errors().len(),
+        time
+    )?;
+    suite.push_str(&cases);
+    writeln!(suite, "  </testsuite>")?;
+    Ok((suite, failures, time))
+}
+
+/// The test case is failed if the status is not expected or the total cost exceeds the threshold.
+///
+/// Unlike [`Thresholds::check`], the status is always checked and default to 2xx.
+fn check_case(thresholds: &Thresholds, resp: &TaskRespone) -> Vec<Violation> {
+    let mut violations = vec![];
+    let status = resp.status();
+    let success = if thresholds.expect_status().is_empty() {
+        (200..300).contains(&status)
+    } else {
+        thresholds.expect_status().contains(&status)
+    };
+
+    if !success {
+        violations.push(Violation::Status {
+            name: resp.loc().to_string(),
+            status,
+        });
+    }
+    if let (Some(total), Some(max)) = (resp.total_cost().duration(), thresholds.max_total()) {
+        if total > max {
+            violations.push(Violation::Total {
+                name: resp.loc().to_string(),
+                total,
+                max,
+            });
+        }
+    }
+    violations
+}
+
+/// Escape the text used in the attribute and content of xml.
+fn escape(text: &str) -> String {
+    let mut ret = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        match ch {
+            '&' => ret.push_str("&amp;"),
+            '<' => ret.push_str("&lt;"),
+            '>' => ret.push_str("&gt;"),
+            '"' => ret.push_str("&quot;"),
+            '\'' => ret.push_str("&apos;"),
+            // control characters are not allowed in xml 1.0
+            ch if ch.is_control() && !matches!(ch, '\n' | '\r' | '\t') => {}
+            ch => ret.push(ch),
+        }
+    }
+    ret
+}
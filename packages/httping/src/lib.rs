@@ -1,8 +1,48 @@
+mod compare;
+mod group;
+mod junit;
 mod server;
+mod stats;
+mod storage;
 mod ui;
+mod watch;
 
+pub use compare::node_key;
+pub use compare::phase_text;
+pub use compare::status_text;
+pub use compare::Comparison;
+pub use compare::NodeDiff;
+pub use compare::PhaseDiff;
+pub use group::group_respone;
+pub use group::Group;
+pub use group::GroupBy;
+pub use group::UNKNOWN_GROUP;
+pub use junit::junit_report;
+pub use server::find_server;
+pub use server::Dns;
 pub use server::Itdog;
+pub use server::LocalHttp;
+pub use server::PingError;
 pub use server::PingServer;
+pub use server::PingStream;
+pub use server::Resolver;
 pub use server::Task;
 pub use server::TaskRespone;
+pub use server::Tcping;
+pub use server::Tls;
+pub use server::STATUS_FAILED;
+pub use server::STATUS_REFUSED;
+pub use server::STATUS_SUCCESS;
+pub use server::STATUS_TIMEOUT;
+pub use stats::class_text;
+pub use stats::summary_row;
+pub use stats::PhaseStats;
+pub use stats::StatusClass;
+pub use stats::Summary;
+pub use stats::TaskStats;
+pub use stats::SUMMARY_HEADER;
+pub use storage::RunRecord;
+pub use storage::Storage;
 pub use ui::Ui;
+pub use watch::Round;
+pub use watch::Watch;
@@ -1,95 +1,309 @@
 use std::fmt::Debug;
-
-use tokio::sync::{mpsc, oneshot};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use ::itdog::HttpMethod;
+use ::itdog::Timing;
+use futures_util::stream::BoxStream;
+use futures_util::StreamExt;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TryRecvError;
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
+mod dns;
 mod itdog;
+mod local;
+mod tcping;
+mod tls;
 
+pub use dns::Dns;
+pub use dns::Resolver;
 pub use itdog::Itdog;
+pub use local::LocalHttp;
+pub use tcping::Tcping;
+pub use tls::Tls;
+
+/// Status of a successful probe which is not a http request.
+pub const STATUS_SUCCESS: i32 = 200;
+
+/// Status of a probe refused by the target.
+pub const STATUS_REFUSED: i32 = -1;
+
+/// Status of a probe not finished in time.
+pub const STATUS_TIMEOUT: i32 = -2;
+
+/// Status of a probe failed with other reason.
+pub const STATUS_FAILED: i32 = -3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PingError {
+    #[error(transparent)]
+    Itdog(#[from] ::itdog::Error),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Other(String),
+}
+
+impl From<color_eyre::Report> for PingError {
+    fn from(value: color_eyre::Report) -> Self {
+        Self::Other(value.to_string())
+    }
+}
+
+/// The respone stream of [`PingServer`], it is finished when all the respone are yielded.
+pub type PingStream = BoxStream<'static, Result<TaskRespone, PingError>>;
 
-#[async_trait::async_trait]
 pub trait PingServer {
     fn name(&self) -> &str;
 
-    async fn ping(
-        &self,
-        host: String,
-        flag: oneshot::Receiver<bool>,
-        out: mpsc::Sender<Option<TaskRespone>>,
-    ) -> color_eyre::Result<()>;
+    /// Ping the host, stop it by cancell the token or drop the stream.
+    fn ping(&self, host: String, cancell: CancellationToken) -> PingStream;
+
+    /// The options of server saved in the history, such as `{"method":"get"}`.
+    fn options(&self) -> String {
+        String::new()
+    }
+
+    /// Return a server using the http method, `None` if the server is not using http.
+    fn with_http_method(&self, _method: HttpMethod) -> Option<Arc<dyn PingServer + Send + Sync>> {
+        None
+    }
+}
+
+/// Find the server by name, the http method is applied if given.
+pub fn find_server(
+    servers: &[Arc<dyn PingServer + Send + Sync>],
+    name: &str,
+    method: Option<HttpMethod>,
+) -> Result<Arc<dyn PingServer + Send + Sync>, PingError> {
+    let server = servers
+        .iter()
+        .find(|v| v.name() == name)
+        .ok_or_else(|| PingError::Other(format!("unknown server `{}`", name)))?;
+
+    match method {
+        Some(method) => server.with_http_method(method).ok_or_else(|| {
+            PingError::Other(format!(
+                "server `{}` not support http method `{}`",
+                name, method
+            ))
+        }),
+        None => Ok(server.clone()),
+    }
+}
+
+/// Parse the target into an uri, the scheme default to `https` if not given.
+pub(crate) fn parse_uri(host: &str) -> color_eyre::Result<http::Uri> {
+    let uri = if host.contains("://") {
+        host.parse::<http::Uri>()?
+    } else {
+        format!("https://{}", host).parse::<http::Uri>()?
+    };
+
+    if uri.host().is_none() {
+        return Err(color_eyre::eyre::eyre!("can not find host in `{}`", host));
+    }
+    Ok(uri)
 }
 
 #[derive(Debug)]
 pub struct Task {
     host: String,
-    handler: Option<JoinHandle<color_eyre::Result<()>>>,
+    server: String,
+    options: String,
+    started_at: SystemTime,
+    ended_at: Option<SystemTime>,
+    handler: Option<JoinHandle<()>>,
     resp: Vec<TaskRespone>,
+    errors: Vec<String>,
 
-    resp_rx: mpsc::Receiver<Option<TaskRespone>>,
-    cancell: Option<oneshot::Sender<bool>>,
+    resp_rx: mpsc::Receiver<Result<TaskRespone, PingError>>,
+    cancell: CancellationToken,
+    cancelled: bool,
     ending: bool,
 }
 
 impl Task {
     pub fn new(
         host: String,
-        handler: JoinHandle<color_eyre::Result<()>>,
-        cancell: oneshot::Sender<bool>,
-        respone: mpsc::Receiver<Option<TaskRespone>>,
+        handler: JoinHandle<()>,
+        cancell: CancellationToken,
+        respone: mpsc::Receiver<Result<TaskRespone, PingError>>,
     ) -> Self {
         Self {
             host,
+            server: String::default(),
+            options: String::default(),
+            started_at: SystemTime::now(),
+            ended_at: None,
             handler: Some(handler),
             resp: vec![],
-            cancell: Some(cancell),
+            errors: vec![],
+            cancell,
+            cancelled: false,
             resp_rx: respone,
             ending: false,
         }
     }
 
+    /// Spawn a task forwarding the respone of [`PingServer::ping`] to the [`Task`].
+    pub fn spawn(
+        runtime: &tokio::runtime::Handle,
+        server: &(dyn PingServer + Send + Sync),
+        host: String,
+    ) -> Self {
+        let (resp_tx, resp_rx) = mpsc::channel(128);
+        let cancell = CancellationToken::new();
+        let mut stream = server.ping(host.clone(), cancell.clone());
+        let handler = runtime.spawn(async move {
+            while let Some(item) = stream.next().await {
+                if resp_tx.send(item).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self::new(host, handler, cancell, resp_rx)
+            .with_server(server.name().to_string())
+            .with_options(server.options())
+    }
+
+    /// Create a task failed with the error, such as the server of target is not found.
+    pub fn failed(runtime: &tokio::runtime::Handle, host: String, error: PingError) -> Self {
+        let (resp_tx, resp_rx) = mpsc::channel(1);
+        let handler = runtime.spawn(async move {
+            let _ = resp_tx.send(Err(error)).await;
+        });
+
+        Self::new(host, handler, CancellationToken::new(), resp_rx)
+    }
+
+    pub fn with_server(mut self, server: String) -> Self {
+        self.server = server;
+        self
+    }
+
+    pub fn with_options(mut self, options: String) -> Self {
+        self.options = options;
+        self
+    }
+
     pub fn host(&self) -> &str {
         self.host.as_str()
     }
 
+    /// The name of server pinging the host.
+    pub fn server(&self) -> &str {
+        self.server.as_str()
+    }
+
+    pub fn options(&self) -> &str {
+        self.options.as_str()
+    }
+
+    pub fn started_at(&self) -> SystemTime {
+        self.started_at
+    }
+
+    /// The time of the task finished, `None` if it is running.
+    pub fn ended_at(&self) -> Option<SystemTime> {
+        self.ended_at
+    }
+
     pub fn respone(&self) -> &[TaskRespone] {
         self.resp.as_slice()
     }
 
-    pub fn try_cancell(&mut self) -> color_eyre::Result<()> {
-        if let Some(tx) = self.cancell.take() {
-            tx.send(true)
-                .map_err(|_| color_eyre::eyre::eyre!("cancell failed"))
-        } else {
-            Ok(())
+    /// Cancel the task if it is not finished, the respone received are kept.
+    pub fn cancell(&mut self) {
+        if !self.ending {
+            self.cancell.cancel();
+            self.cancelled = true;
         }
     }
 
+    pub fn cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    /// Receive all the pending respone, the task is ending when the stream is finished.
     pub fn recv_respone(&mut self) {
-        if !self.ending {
-            let ret = self.resp_rx.try_recv();
+        while !self.ending {
+            match self.resp_rx.try_recv() {
+                Ok(Ok(resp)) => {
+                    self.resp.push(resp);
+                }
+                Ok(Err(e)) => {
+                    self.errors.push(e.to_string());
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.finish();
+                }
+            }
+        }
+    }
 
-            match ret {
-                Ok(Some(resp)) => {
+    /// Wait the next respone, return `None` if the task is finished.
+    ///
+    /// The errors received before the respone are kept in [`Task::errors`].
+    pub async fn recv(&mut self) -> Option<&TaskRespone> {
+        while !self.ending {
+            match self.resp_rx.recv().await {
+                Some(Ok(resp)) => {
                     self.resp.push(resp);
+                    return self.resp.last();
+                }
+                Some(Err(e)) => {
+                    self.errors.push(e.to_string());
                 }
-                Ok(None) => {
-                    self.ending = true;
+                None => {
+                    self.finish();
                 }
-                Err(_) => {}
             }
         }
+        None
     }
 
-    pub fn take_handler(&mut self) -> Option<JoinHandle<color_eyre::Result<()>>> {
+    fn finish(&mut self) {
+        self.ending = true;
+        self.ended_at = Some(SystemTime::now());
+    }
+
+    /// Wait until the task is finished and receive all the respone.
+    pub async fn wait(&mut self) {
+        while self.recv().await.is_some() {}
+    }
+
+    pub fn take_handler(&mut self) -> Option<JoinHandle<()>> {
         self.handler.take()
     }
 
     pub fn ending(&self) -> bool {
         self.ending
     }
+
+    /// The first failure reason of task.
+    pub fn error(&self) -> Option<&str> {
+        self.errors.first().map(String::as_str)
+    }
+
+    pub fn errors(&self) -> &[String] {
+        self.errors.as_slice()
+    }
+}
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        self.cancell.cancel();
+    }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, serde::Serialize)]
 pub struct TaskRespone {
     loc: String,
 
@@ -97,15 +311,19 @@ pub struct TaskRespone {
 
     status: i32,
 
-    total_cost: String,
+    total_cost: Timing,
 
     other_name_list: Vec<String>,
 
-    other_cost_list: Vec<String>,
+    other_cost_list: Vec<Timing>,
+
+    /// The information such as protocol version, which is not a timing.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    other_info: Vec<(String, String)>,
 
     redirect: i32,
 
-    redirect_cost: String,
+    redirect_cost: Timing,
 }
 
 impl TaskRespone {
@@ -124,8 +342,8 @@ impl TaskRespone {
         self
     }
 
-    pub fn with_total_cost(mut self, total_cost: String) -> Self {
-        self.total_cost = total_cost;
+    pub fn with_total_cost(mut self, total_cost: impl Into<Timing>) -> Self {
+        self.total_cost = total_cost.into();
         self
     }
 
@@ -134,18 +352,23 @@ impl TaskRespone {
         self
     }
 
-    pub fn with_other_cost_list(mut self, other_cost_list: Vec<String>) -> Self {
+    pub fn with_other_cost_list(mut self, other_cost_list: Vec<Timing>) -> Self {
         self.other_cost_list = other_cost_list;
         self
     }
 
+    pub fn with_other_info(mut self, other_info: Vec<(String, String)>) -> Self {
+        self.other_info = other_info;
+        self
+    }
+
     pub fn with_redirect(mut self, redirect: i32) -> Self {
         self.redirect = redirect;
         self
     }
 
-    pub fn with_redirect_cost(mut self, redirect_cost: String) -> Self {
-        self.redirect_cost = redirect_cost;
+    pub fn with_redirect_cost(mut self, redirect_cost: impl Into<Timing>) -> Self {
+        self.redirect_cost = redirect_cost.into();
         self
     }
 
@@ -161,24 +384,29 @@ impl TaskRespone {
         self.status
     }
 
-    pub fn total_cost(&self) -> &str {
-        self.total_cost.as_str()
+    pub fn total_cost(&self) -> &Timing {
+        &self.total_cost
     }
 
     pub fn other_name_list(&self) -> &[String] {
         self.other_name_list.as_slice()
     }
 
-    pub fn other_cost_list(&self) -> &[String] {
+    pub fn other_cost_list(&self) -> &[Timing] {
         self.other_cost_list.as_slice()
     }
 
+    /// The name and value of information.
+    pub fn other_info(&self) -> &[(String, String)] {
+        self.other_info.as_slice()
+    }
+
     pub fn redirect(&self) -> i32 {
         self.redirect
     }
 
-    pub fn redirect_cost(&self) -> &str {
-        self.redirect_cost.as_str()
+    pub fn redirect_cost(&self) -> &Timing {
+        &self.redirect_cost
     }
 
     pub fn set_loc(&mut self, loc: String) -> &mut Self {
@@ -196,8 +424,8 @@ impl TaskRespone {
         self
     }
 
-    pub fn set_total_cost(&mut self, total_cost: String) -> &mut Self {
-        self.total_cost = total_cost;
+    pub fn set_total_cost(&mut self, total_cost: impl Into<Timing>) -> &mut Self {
+        self.total_cost = total_cost.into();
         self
     }
 
@@ -206,18 +434,23 @@ impl TaskRespone {
         self
     }
 
-    pub fn set_other_cost_list(&mut self, other_cost_list: Vec<String>) -> &mut Self {
+    pub fn set_other_cost_list(&mut self, other_cost_list: Vec<Timing>) -> &mut Self {
         self.other_cost_list = other_cost_list;
         self
     }
 
+    pub fn set_other_info(&mut self, other_info: Vec<(String, String)>) -> &mut Self {
+        self.other_info = other_info;
+        self
+    }
+
     pub fn set_redirect(&mut self, redirect: i32) -> &mut Self {
         self.redirect = redirect;
         self
     }
 
-    pub fn set_redirect_cost(&mut self, redirect_cost: String) -> &mut Self {
-        self.redirect_cost = redirect_cost;
+    pub fn set_redirect_cost(&mut self, redirect_cost: impl Into<Timing>) -> &mut Self {
+        self.redirect_cost = redirect_cost.into();
         self
     }
 }
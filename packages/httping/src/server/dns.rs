@@ -0,0 +1,288 @@
+use std::fmt::Display;
+use std::net::IpAddr;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+use std::time::Instant;
+
+use hickory_resolver::config::LookupIpStrategy;
+use hickory_resolver::config::NameServerConfig;
+use hickory_resolver::config::Protocol;
+use hickory_resolver::config::ResolverConfig;
+use hickory_resolver::config::ResolverOpts;
+use hickory_resolver::TokioAsyncResolver;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, trace};
+
+use super::{parse_uri, PingError, PingServer, PingStream, TaskRespone};
+use super::{STATUS_FAILED, STATUS_SUCCESS, STATUS_TIMEOUT};
+
+const DNS_PORT: u16 = 53;
+
+/// The resolver used to resolve the target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolver {
+    /// Resolve with the system configuration.
+    System,
+
+    /// Send the query to the dns server over udp.
+    Udp(SocketAddr),
+
+    /// Send the query to the dns server over tcp.
+    Tcp(SocketAddr),
+
+    /// Send the query to the DoH server using the json api, for example `https://dns.google/resolve`.
+    Doh(String),
+}
+
+impl Resolver {
+    fn parse_addr(addr: &str) -> color_eyre::Result<SocketAddr> {
+        if let Ok(addr) = addr.parse::<SocketAddr>() {
+            Ok(addr)
+        } else {
+            Ok(SocketAddr::new(addr.parse::<IpAddr>()?, DNS_PORT))
+        }
+    }
+}
+
+impl FromStr for Resolver {
+    type Err = color_eyre::Report;
+
+    /// Parse resolver from `system`, `udp://8.8.8.8`, `tcp://1.1.1.1:53`,
+    /// `https://dns.google/resolve` or `8.8.8.8`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "system" {
+            Ok(Self::System)
+        } else if let Some(addr) = s.strip_prefix("udp://") {
+            Ok(Self::Udp(Self::parse_addr(addr)?))
+        } else if let Some(addr) = s.strip_prefix("tcp://") {
+            Ok(Self::Tcp(Self::parse_addr(addr)?))
+        } else if s.starts_with("https://") || s.starts_with("http://") {
+            Ok(Self::Doh(s.to_string()))
+        } else {
+            Ok(Self::Udp(Self::parse_addr(s).map_err(|_| {
+                color_eyre::eyre::eyre!("invalid resolver `{}`", s)
+            })?))
+        }
+    }
+}
+
+impl Display for Resolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Resolver::System => write!(f, "system"),
+            Resolver::Udp(addr) => write!(f, "udp://{}", addr),
+            Resolver::Tcp(addr) => write!(f, "tcp://{}", addr),
+            Resolver::Doh(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct DohAnswer {
+    #[serde(rename = "type")]
+    ty: u16,
+
+    data: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct DohRespone {
+    #[serde(rename = "Status")]
+    status: u32,
+
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DohAnswer>,
+}
+
+/// Resolve the target against each of the resolvers, report the answers and latency.
+#[derive(Debug, Clone)]
+pub struct Dns {
+    resolvers: Vec<Resolver>,
+
+    timeout: Duration,
+}
+
+impl Default for Dns {
+    fn default() -> Self {
+        Self {
+            resolvers: vec![
+                Resolver::System,
+                Resolver::Udp(SocketAddr::new([223, 5, 5, 5].into(), DNS_PORT)),
+                Resolver::Udp(SocketAddr::new([119, 29, 29, 29].into(), DNS_PORT)),
+                Resolver::Udp(SocketAddr::new([8, 8, 8, 8].into(), DNS_PORT)),
+                Resolver::Tcp(SocketAddr::new([1, 1, 1, 1].into(), DNS_PORT)),
+                Resolver::Doh("https://dns.alidns.com/resolve".to_string()),
+            ],
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl Dns {
+    pub fn with_resolvers(mut self, resolvers: Vec<Resolver>) -> Self {
+        self.resolvers = resolvers;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn resolvers(&self) -> &[Resolver] {
+        self.resolvers.as_slice()
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Resolve the host using given resolver, return the answers.
+    pub async fn lookup(&self, resolver: &Resolver, host: &str) -> color_eyre::Result<Vec<IpAddr>> {
+        match resolver {
+            Resolver::System => Ok(tokio::net::lookup_host((host, 0))
+                .await?
+                .map(|addr| addr.ip())
+                .collect()),
+            Resolver::Udp(addr) => Self::lookup_server(*addr, Protocol::Udp, host).await,
+            Resolver::Tcp(addr) => Self::lookup_server(*addr, Protocol::Tcp, host).await,
+            Resolver::Doh(url) => {
+                // query both A and AAAA, same as the other resolvers
+                let client = reqwest::Client::new();
+                let (v4, v6) = tokio::try_join!(
+                    Self::lookup_doh(&client, url, host, "A"),
+                    Self::lookup_doh(&client, url, host, "AAAA"),
+                )?;
+
+                Ok(v4.into_iter().chain(v6).collect())
+            }
+        }
+    }
+
+    async fn lookup_doh(
+        client: &reqwest::Client,
+        url: &str,
+        host: &str,
+        ty: &str,
+    ) -> color_eyre::Result<Vec<IpAddr>> {
+        let text = client
+            .get(url)
+            .query(&[("name", host), ("type", ty)])
+            .header(reqwest::header::ACCEPT, "application/dns-json")
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let respone: DohRespone = serde_json::from_str(&text)?;
+
+        if respone.status != 0 {
+            return Err(color_eyre::eyre::eyre!(
+                "DoH server reply with status {}",
+                respone.status
+            ));
+        }
+        Ok(respone
+            .answer
+            .iter()
+            .filter(|v| v.ty == 1 || v.ty == 28)
+            .filter_map(|v| v.data.parse::<IpAddr>().ok())
+            .collect())
+    }
+
+    async fn lookup_server(
+        addr: SocketAddr,
+        protocol: Protocol,
+        host: &str,
+    ) -> color_eyre::Result<Vec<IpAddr>> {
+        let config =
+            ResolverConfig::from_parts(None, vec![], vec![NameServerConfig::new(addr, protocol)]);
+        let mut opts = ResolverOpts::default();
+
+        opts.cache_size = 0;
+        opts.attempts = 1;
+        opts.ip_strategy = LookupIpStrategy::Ipv4AndIpv6;
+
+        let resolver = TokioAsyncResolver::tokio(config, opts);
+
+        Ok(resolver.lookup_ip(host).await?.iter().collect())
+    }
+
+    /// Resolve the host once, the result is reported as a [`TaskRespone`].
+    ///
+    /// The answers are sorted and deduplicated, so they are comparable across resolvers.
+    pub async fn resolve(&self, resolver: &Resolver, host: &str) -> TaskRespone {
+        let start = Instant::now();
+        let ret = tokio::time::timeout(self.timeout, self.lookup(resolver, host)).await;
+        let cost = start.elapsed();
+        let (ip, status) = match ret {
+            Ok(Ok(mut answers)) if !answers.is_empty() => {
+                answers.sort();
+                answers.dedup();
+                (
+                    answers
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    STATUS_SUCCESS,
+                )
+            }
+            Ok(Ok(_)) => ("-".to_string(), STATUS_FAILED),
+            Ok(Err(e)) => {
+                debug!("resolve `{host}` with `{resolver}` failed: {e:?}");
+                ("-".to_string(), STATUS_FAILED)
+            }
+            Err(_) => ("-".to_string(), STATUS_TIMEOUT),
+        };
+
+        TaskRespone::default()
+            .with_loc(resolver.to_string())
+            .with_ip(ip)
+            .with_status(status)
+            .with_total_cost(cost)
+            .with_redirect(0)
+            .with_redirect_cost(Duration::ZERO)
+    }
+}
+
+impl PingServer for Dns {
+    fn name(&self) -> &str {
+        "dns"
+    }
+
+    fn ping(&self, host: String, cancell: CancellationToken) -> PingStream {
+        let server = self.clone();
+
+        Box::pin(async_stream::stream! {
+            debug!("start dns request for `{host}`");
+
+            let uri = match parse_uri(&host) {
+                Ok(uri) => uri,
+                Err(e) => {
+                    yield Err(PingError::from(e));
+                    return;
+                }
+            };
+            let server_host = uri
+                .host()
+                .unwrap_or_default()
+                .trim_start_matches('[')
+                .trim_end_matches(']');
+
+            for resolver in server.resolvers.iter() {
+                let task_resp = tokio::select! {
+                    task_resp = server.resolve(resolver, server_host) => task_resp,
+                    _ = cancell.cancelled() => return,
+                };
+
+                trace!(
+                    "sending respone resolver = `{resolver}`, status = `{}`",
+                    task_resp.status()
+                );
+                yield Ok(task_resp);
+            }
+        })
+    }
+}
@@ -1,63 +1,124 @@
+use std::sync::Arc;
+
+use itdog::HttpMethod;
 use itdog::ItdogClient;
-use tokio::sync::mpsc::Sender;
-use tokio::sync::oneshot::Receiver;
+use itdog::Message;
+use itdog::QueryOptions;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, trace};
 
-use super::{PingServer, TaskRespone};
+use super::{PingError, PingServer, PingStream, TaskRespone};
+
+#[derive(Debug, Clone)]
+pub struct Itdog {
+    client: ItdogClient,
+
+    options: QueryOptions,
+}
+
+impl Itdog {
+    pub fn new(client: ItdogClient) -> Self {
+        Self {
+            client,
+            options: QueryOptions::default(),
+        }
+    }
+
+    pub fn with_options(mut self, options: QueryOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn client(&self) -> &ItdogClient {
+        &self.client
+    }
+
+    pub fn options(&self) -> &QueryOptions {
+        &self.options
+    }
+}
 
-pub struct Itdog;
+fn respone_of(msg: &Message) -> TaskRespone {
+    TaskRespone::default()
+        .with_loc(msg.name().to_string())
+        .with_ip(msg.ip().to_string())
+        .with_status(msg.http_code())
+        .with_redirect(msg.redirect())
+        .with_redirect_cost(msg.redirect_time().clone())
+        .with_total_cost(msg.all_time().clone())
+        .with_other_name_list(
+            ["DNS时间", "连接时间", "下载时间"]
+                .map(String::from)
+                .to_vec(),
+        )
+        .with_other_cost_list(
+            [msg.dns_time(), msg.connect_time(), msg.download_time()]
+                .map(Clone::clone)
+                .to_vec(),
+        )
+}
 
-#[async_trait::async_trait]
 impl PingServer for Itdog {
     fn name(&self) -> &str {
         "itdog"
     }
 
-    async fn ping(
-        &self,
-        host: String,
-        cancell: Receiver<bool>,
-        resp: Sender<Option<TaskRespone>>,
-    ) -> color_eyre::Result<()> {
-        let (send, mut recv) = tokio::sync::mpsc::channel(128);
-
-        debug!("start ping request for `{host}`");
-
-        tokio::spawn(async move {
-            let mut itdog = ItdogClient::new(itdog::DEFAULT_KEY, &host, cancell, send);
-            itdog.query().await.unwrap();
-        });
-
-        while let Some(msg) = recv.recv().await {
-            let task_resp = TaskRespone::default()
-                .with_loc(msg.name().to_string())
-                .with_ip(msg.ip().to_string())
-                .with_status(msg.http_code())
-                .with_redirect(msg.redirect())
-                .with_redirect_cost(msg.redirect_time().to_string())
-                .with_total_cost(msg.all_time().to_string())
-                .with_other_name_list(
-                    ["DNS时间", "连接时间", "下载时间"]
-                        .map(String::from)
-                        .to_vec(),
-                )
-                .with_other_cost_list(
-                    [msg.dns_time(), msg.connect_time(), msg.download_time()]
-                        .map(String::from)
-                        .to_vec(),
-                );
-
-            trace!(
-                "sending respone ip = `{}`, status = `{}`",
-                msg.ip(),
-                msg.http_code()
-            );
-
-            resp.send(Some(task_resp)).await?;
-        }
+    fn options(&self) -> String {
+        serde_json::to_string(&self.options).unwrap_or_default()
+    }
+
+    fn with_http_method(&self, method: HttpMethod) -> Option<Arc<dyn PingServer + Send + Sync>> {
+        Some(Arc::new(
+            self.clone()
+                .with_options(self.options.clone().with_method(method)),
+        ))
+    }
+
+    fn ping(&self, host: String, cancell: CancellationToken) -> PingStream {
+        let client = self.client.clone();
+        let options = self.options.clone();
+
+        Box::pin(async_stream::stream! {
+            let (send, mut recv) = mpsc::channel(128);
+            let mut ret = None;
+
+            debug!("start ping request for `{host}`");
+
+            let query = client.query(&host, &options, cancell, send);
+
+            tokio::pin!(query);
+            loop {
+                // the message channel is closed after the query finished
+                let msg = tokio::select! {
+                    msg = recv.recv() => msg,
+                    query_ret = &mut query, if ret.is_none() => {
+                        ret = Some(query_ret);
+                        continue;
+                    }
+                };
 
-        resp.send(None).await?;
+                match msg {
+                    Some(msg) => {
+                        trace!(
+                            "sending respone ip = `{}`, status = `{}`",
+                            msg.ip(),
+                            msg.http_code()
+                        );
+                        yield Ok(respone_of(&msg));
+                    }
+                    None => break,
+                }
+            }
+            let ret = match ret {
+                Some(ret) => ret,
+                None => query.await,
+            };
 
-        Ok(())
+            match ret {
+                Ok(_) | Err(itdog::Error::Cancelled) => {}
+                Err(e) => yield Err(PingError::from(e)),
+            }
+        })
     }
 }
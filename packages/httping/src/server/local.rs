@@ -0,0 +1,259 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use http::header::HOST;
+use http::header::USER_AGENT;
+use http::Method;
+use http::Uri;
+use http_body_util::BodyExt;
+use http_body_util::Empty;
+use hyper::body::Bytes;
+use hyper_util::rt::TokioIo;
+use itdog::HttpMethod;
+use itdog::Timing;
+use rustls::ClientConfig;
+use rustls::RootCertStore;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, trace};
+
+use super::{parse_uri, PingError, PingServer, PingStream, TaskRespone};
+
+/// Probe the target from this machine, record the cost of each phase.
+#[derive(Debug, Clone)]
+pub struct LocalHttp {
+    method: Method,
+
+    useragent: String,
+
+    timeout: Duration,
+}
+
+impl Default for LocalHttp {
+    fn default() -> Self {
+        Self {
+            method: Method::GET,
+            useragent: concat!("httping/", env!("CARGO_PKG_VERSION")).to_string(),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl LocalHttp {
+    pub fn with_method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    pub fn with_useragent(mut self, useragent: String) -> Self {
+        self.useragent = useragent;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    pub fn useragent(&self) -> &str {
+        self.useragent.as_str()
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub async fn probe(&self, host: &str) -> color_eyre::Result<TaskRespone> {
+        tokio::time::timeout(self.timeout, self.probe_phases(host))
+            .await
+            .map_err(|_| color_eyre::eyre::eyre!("probe `{}` timeout", host))?
+    }
+
+    async fn probe_phases(&self, host: &str) -> color_eyre::Result<TaskRespone> {
+        let uri = parse_uri(host)?;
+        let https = uri.scheme_str() != Some("http");
+        let server_host = uri
+            .host()
+            .unwrap_or_default()
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+        let port = uri.port_u16().unwrap_or(if https { 443 } else { 80 });
+
+        let start = Instant::now();
+        let addr: SocketAddr = tokio::net::lookup_host((server_host.as_str(), port))
+            .await?
+            .next()
+            .ok_or_else(|| color_eyre::eyre::eyre!("can not resolve host `{}`", server_host))?;
+        let dns_cost = start.elapsed();
+
+        debug!("resolve `{server_host}` to `{addr}`");
+
+        let start = Instant::now();
+        let tcp_stream = TcpStream::connect(addr).await?;
+        let connect_cost = start.elapsed();
+
+        debug!("connected to `{addr}`");
+
+        let (tls_cost, status, first_byte_cost, download_cost) = if https {
+            let mut config = ClientConfig::builder()
+                .with_root_certificates(RootCertStore::from_iter(
+                    webpki_roots::TLS_SERVER_ROOTS.iter().cloned(),
+                ))
+                .with_no_client_auth();
+
+            config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+            let tls_connector = TlsConnector::from(Arc::new(config));
+            let server_name =
+                tokio_rustls::rustls::pki_types::ServerName::try_from(server_host.clone())?;
+
+            let start = Instant::now();
+            let tls_stream = tls_connector.connect(server_name, tcp_stream).await?;
+            let tls_cost = start.elapsed();
+
+            debug!("tls handshake with `{server_host}` done");
+
+            let (status, first_byte_cost, download_cost) = self.request(&uri, tls_stream).await?;
+
+            (tls_cost, status, first_byte_cost, download_cost)
+        } else {
+            let (status, first_byte_cost, download_cost) = self.request(&uri, tcp_stream).await?;
+
+            (Duration::ZERO, status, first_byte_cost, download_cost)
+        };
+        let total_cost = dns_cost + connect_cost + tls_cost + first_byte_cost + download_cost;
+
+        Ok(TaskRespone::default()
+            .with_loc("本机".to_string())
+            .with_ip(addr.ip().to_string())
+            .with_status(status)
+            .with_total_cost(total_cost)
+            .with_redirect(0)
+            .with_redirect_cost(Duration::ZERO)
+            .with_other_name_list(
+                [
+                    "DNS时间",
+                    "连接时间",
+                    "TLS握手时间",
+                    "首字节时间",
+                    "下载时间",
+                ]
+                .map(String::from)
+                .to_vec(),
+            )
+            .with_other_cost_list(
+                [
+                    dns_cost,
+                    connect_cost,
+                    tls_cost,
+                    first_byte_cost,
+                    download_cost,
+                ]
+                .map(Timing::from)
+                .to_vec(),
+            ))
+    }
+
+    /// Send the request over the stream, return the status, time to first byte and download cost.
+    async fn request<S>(
+        &self,
+        uri: &Uri,
+        stream: S,
+    ) -> color_eyre::Result<(i32, Duration, Duration)>
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let (mut sender, conn) =
+            hyper::client::conn::http1::handshake(TokioIo::new(stream)).await?;
+
+        tokio::spawn(async move {
+            if let Err(e) = conn.await {
+                debug!("connection closed with error: {e:?}");
+            }
+        });
+
+        let request = http::Request::builder()
+            .method(self.method.clone())
+            .uri(uri.path_and_query().map(|v| v.as_str()).unwrap_or("/"))
+            .header(
+                HOST,
+                uri.authority().map(|v| v.as_str()).unwrap_or_default(),
+            )
+            .header(USER_AGENT, self.useragent.as_str())
+            .body(Empty::<Bytes>::new())?;
+
+        debug!("sending http request: `{request:?}`");
+
+        let start = Instant::now();
+        let respone = sender.send_request(request).await?;
+        let first_byte_cost = start.elapsed();
+        let status = respone.status().as_u16() as i32;
+        let mut body = respone.into_body();
+        let mut count = 0;
+
+        let start = Instant::now();
+        while let Some(frame) = body.frame().await {
+            if let Some(data) = frame?.data_ref() {
+                count += data.len();
+            }
+        }
+        let download_cost = start.elapsed();
+
+        trace!("got status = `{status}`, body length = {count}");
+
+        Ok((status, first_byte_cost, download_cost))
+    }
+}
+
+impl PingServer for LocalHttp {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn options(&self) -> String {
+        serde_json::json!({
+            "method": self.method.as_str(),
+            "timeout": self.timeout.as_secs_f64(),
+        })
+        .to_string()
+    }
+
+    fn with_http_method(&self, method: HttpMethod) -> Option<Arc<dyn PingServer + Send + Sync>> {
+        Some(Arc::new(self.clone().with_method(method.into())))
+    }
+
+    fn ping(&self, host: String, cancell: CancellationToken) -> PingStream {
+        let server = self.clone();
+
+        Box::pin(async_stream::stream! {
+            debug!("start local http request for `{host}`");
+
+            let ret = tokio::select! {
+                ret = server.probe(&host) => ret,
+                _ = cancell.cancelled() => return,
+            };
+
+            match ret {
+                Ok(task_resp) => {
+                    trace!(
+                        "sending respone ip = `{}`, status = `{}`",
+                        task_resp.ip(),
+                        task_resp.status()
+                    );
+                    yield Ok(task_resp);
+                }
+                Err(e) => yield Err(PingError::from(e)),
+            }
+        })
+    }
+}
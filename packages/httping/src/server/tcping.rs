@@ -0,0 +1,165 @@
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::time::Duration;
+use std::time::Instant;
+
+use tokio::net::TcpStream;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, trace};
+
+use super::{parse_uri, PingError, PingServer, PingStream, TaskRespone};
+use super::{STATUS_FAILED, STATUS_REFUSED, STATUS_SUCCESS, STATUS_TIMEOUT};
+
+/// Measure the tcp handshake latency of `host:port`.
+///
+/// Each attempt is reported as a [`TaskRespone`], the status is one of
+/// [`STATUS_SUCCESS`], [`STATUS_REFUSED`], [`STATUS_TIMEOUT`] and [`STATUS_FAILED`].
+#[derive(Debug, Clone)]
+pub struct Tcping {
+    count: usize,
+
+    port: u16,
+
+    interval: Duration,
+
+    timeout: Duration,
+}
+
+impl Default for Tcping {
+    fn default() -> Self {
+        Self {
+            count: 4,
+            port: 80,
+            interval: Duration::from_secs(1),
+            timeout: Duration::from_secs(3),
+        }
+    }
+}
+
+impl Tcping {
+    pub fn with_count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Resolve the target, the port default to [`port`](Tcping::port) if not given.
+    pub async fn resolve(&self, host: &str) -> color_eyre::Result<SocketAddr> {
+        let uri = parse_uri(host)?;
+        let server_host = uri
+            .host()
+            .unwrap_or_default()
+            .trim_start_matches('[')
+            .trim_end_matches(']');
+        let port = uri.port_u16().unwrap_or(self.port);
+        let addr = tokio::net::lookup_host((server_host, port))
+            .await?
+            .next()
+            .ok_or_else(|| color_eyre::eyre::eyre!("can not resolve host `{}`", server_host))?;
+
+        Ok(addr)
+    }
+
+    /// Connect to the address once, the `attempt` is used as the location of respone.
+    pub async fn connect(&self, attempt: usize, addr: SocketAddr) -> TaskRespone {
+        let start = Instant::now();
+        let ret = tokio::time::timeout(self.timeout, TcpStream::connect(addr)).await;
+        let cost = start.elapsed();
+        let (loc, status) = match ret {
+            Ok(Ok(stream)) => {
+                let local = stream
+                    .local_addr()
+                    .map(|v| v.to_string())
+                    .unwrap_or_default();
+
+                (format!("#{} {}", attempt, local), STATUS_SUCCESS)
+            }
+            Ok(Err(e)) if e.kind() == ErrorKind::ConnectionRefused => {
+                (format!("#{} 拒绝", attempt), STATUS_REFUSED)
+            }
+            Ok(Err(e)) => {
+                debug!("connect to `{addr}` failed: {e:?}");
+                (format!("#{} 失败", attempt), STATUS_FAILED)
+            }
+            Err(_) => (format!("#{} 超时", attempt), STATUS_TIMEOUT),
+        };
+
+        TaskRespone::default()
+            .with_loc(loc)
+            .with_ip(addr.to_string())
+            .with_status(status)
+            .with_total_cost(cost)
+            .with_redirect(0)
+            .with_redirect_cost(Duration::ZERO)
+    }
+}
+
+impl PingServer for Tcping {
+    fn name(&self) -> &str {
+        "tcping"
+    }
+
+    fn ping(&self, host: String, cancell: CancellationToken) -> PingStream {
+        let server = self.clone();
+
+        Box::pin(async_stream::stream! {
+            debug!("start tcping request for `{host}`");
+
+            let addr = match server.resolve(&host).await {
+                Ok(addr) => addr,
+                Err(e) => {
+                    yield Err(PingError::from(e));
+                    return;
+                }
+            };
+
+            for attempt in 1..=server.count {
+                let task_resp = tokio::select! {
+                    task_resp = async {
+                        if attempt > 1 {
+                            tokio::time::sleep(server.interval).await;
+                        }
+                        server.connect(attempt, addr).await
+                    } => task_resp,
+                    _ = cancell.cancelled() => return,
+                };
+
+                trace!(
+                    "sending respone attempt = `{attempt}`, status = `{}`",
+                    task_resp.status()
+                );
+                yield Ok(task_resp);
+            }
+        })
+    }
+}
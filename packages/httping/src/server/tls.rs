@@ -0,0 +1,321 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use itdog::Timing;
+use rustls::client::danger::HandshakeSignatureValid;
+use rustls::client::danger::ServerCertVerified;
+use rustls::client::danger::ServerCertVerifier;
+use rustls::client::WebPkiServerVerifier;
+use rustls::pki_types::CertificateDer;
+use rustls::pki_types::ServerName;
+use rustls::pki_types::UnixTime;
+use rustls::ClientConfig;
+use rustls::DigitallySignedStruct;
+use rustls::RootCertStore;
+use rustls::SignatureScheme;
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::TlsConnector;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, trace};
+
+use super::{parse_uri, PingError, PingServer, PingStream, TaskRespone};
+use super::{STATUS_FAILED, STATUS_SUCCESS, STATUS_TIMEOUT};
+
+/// The cost of connect and handshake, the stream, the certificate chain and the error of
+/// verification.
+type Handshake = (
+    Duration,
+    Duration,
+    TlsStream<TcpStream>,
+    Vec<CertificateDer<'static>>,
+    Option<rustls::Error>,
+);
+
+/// Verify the certificates with webpki, the chain and the error of verification are recorded
+/// instead of aborting the handshake, so the expired or untrusted certificate can be inspected.
+#[derive(Debug)]
+struct RecordVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+
+    record: Mutex<(Vec<CertificateDer<'static>>, Option<rustls::Error>)>,
+}
+
+impl RecordVerifier {
+    fn new() -> color_eyre::Result<Self> {
+        let roots = RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+
+        Ok(Self {
+            inner: WebPkiServerVerifier::builder(Arc::new(roots)).build()?,
+            record: Mutex::new((vec![], None)),
+        })
+    }
+
+    /// The certificate chain and the error of verification.
+    fn take(&self) -> (Vec<CertificateDer<'static>>, Option<rustls::Error>) {
+        std::mem::take(&mut *self.record.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+impl ServerCertVerifier for RecordVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let ret = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        );
+        let mut certs = vec![end_entity.clone().into_owned()];
+
+        certs.extend(intermediates.iter().map(|v| v.clone().into_owned()));
+        *self.record.lock().unwrap_or_else(|e| e.into_inner()) = (certs, ret.err());
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// Connect to the target with tls, inspect the handshake and the certificate chain.
+///
+/// The cost of connect and handshake are reported as other columns, the handshake information,
+/// the result of verification and expiry date of certificates are reported as other information.
+/// The status is failed if the verification failed or the certificate of server is expired.
+#[derive(Debug, Clone)]
+pub struct Tls {
+    port: u16,
+
+    alpn: Vec<String>,
+
+    timeout: Duration,
+}
+
+impl Default for Tls {
+    fn default() -> Self {
+        Self {
+            port: 443,
+            alpn: vec!["h2".to_string(), "http/1.1".to_string()],
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Tls {
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    pub fn with_alpn(mut self, alpn: Vec<String>) -> Self {
+        self.alpn = alpn;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn alpn(&self) -> &[String] {
+        self.alpn.as_slice()
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub async fn inspect(&self, host: &str) -> color_eyre::Result<TaskRespone> {
+        let uri = parse_uri(host)?;
+        let server_host = uri
+            .host()
+            .unwrap_or_default()
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+        let port = uri.port_u16().unwrap_or(self.port);
+        let addr: SocketAddr = tokio::net::lookup_host((server_host.as_str(), port))
+            .await?
+            .next()
+            .ok_or_else(|| color_eyre::eyre::eyre!("can not resolve host `{}`", server_host))?;
+        let task_resp = TaskRespone::default()
+            .with_loc(server_host.clone())
+            .with_ip(addr.to_string())
+            .with_redirect(0)
+            .with_redirect_cost(Duration::ZERO);
+
+        debug!("resolve `{server_host}` to `{addr}`");
+
+        let start = Instant::now();
+        let ret = tokio::time::timeout(self.timeout, self.handshake(&server_host, addr)).await;
+
+        match ret {
+            Ok(Ok((connect_cost, handshake_cost, tls_stream, certs, error))) => {
+                let (_, conn) = tls_stream.get_ref();
+                let now = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)?
+                    .as_secs() as i64;
+                let mut status = STATUS_SUCCESS;
+                let mut info = vec![
+                    (
+                        "协议版本".to_string(),
+                        conn.protocol_version()
+                            .map(|v| format!("{:?}", v))
+                            .unwrap_or_else(|| "-".to_string()),
+                    ),
+                    (
+                        "加密套件".to_string(),
+                        conn.negotiated_cipher_suite()
+                            .map(|v| format!("{:?}", v.suite()))
+                            .unwrap_or_else(|| "-".to_string()),
+                    ),
+                    (
+                        "ALPN".to_string(),
+                        conn.alpn_protocol()
+                            .map(|v| String::from_utf8_lossy(v).to_string())
+                            .unwrap_or_else(|| "-".to_string()),
+                    ),
+                ];
+
+                match error {
+                    Some(e) => {
+                        debug!("verify certificate of `{server_host}` failed: {e:?}");
+                        status = STATUS_FAILED;
+                        info.push(("证书验证".to_string(), format!("失败: {}", e)));
+                    }
+                    None => info.push(("证书验证".to_string(), "通过".to_string())),
+                }
+                for (index, cert) in certs.iter().enumerate() {
+                    let (_, cert) = x509_parser::parse_x509_certificate(cert.as_ref())?;
+                    let not_after = cert.validity().not_after;
+                    let left_days = (not_after.timestamp() - now) / 86400;
+                    let name = cert
+                        .subject()
+                        .iter_common_name()
+                        .next()
+                        .and_then(|v| v.as_str().ok())
+                        .map(String::from)
+                        .unwrap_or_else(|| format!("证书{}", index));
+
+                    trace!("certificate `{name}` expire at {}", not_after.to_datetime());
+                    if index == 0 && left_days < 0 {
+                        status = STATUS_FAILED;
+                    }
+                    info.push((
+                        format!("{} 到期", name),
+                        format!("{} ({}天)", not_after.to_datetime().date(), left_days),
+                    ));
+                }
+
+                Ok(task_resp
+                    .with_status(status)
+                    .with_total_cost(connect_cost + handshake_cost)
+                    .with_other_name_list(["连接时间", "握手时间"].map(String::from).to_vec())
+                    .with_other_cost_list(vec![
+                        Timing::from(connect_cost),
+                        Timing::from(handshake_cost),
+                    ])
+                    .with_other_info(info))
+            }
+            Ok(Err(e)) => Err(e.wrap_err(format!("tls handshake with `{}` failed", server_host))),
+            Err(_) => Ok(task_resp
+                .with_status(STATUS_TIMEOUT)
+                .with_total_cost(start.elapsed())),
+        }
+    }
+
+    /// Connect to the address and do the tls handshake, return the cost of connect and handshake,
+    /// the certificate chain and the error of verification.
+    async fn handshake(
+        &self,
+        server_host: &str,
+        addr: SocketAddr,
+    ) -> color_eyre::Result<Handshake> {
+        let verifier = Arc::new(RecordVerifier::new()?);
+        let mut config = ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(verifier.clone())
+            .with_no_client_auth();
+
+        config.alpn_protocols = self.alpn.iter().map(|v| v.as_bytes().to_vec()).collect();
+
+        let tls_connector = TlsConnector::from(Arc::new(config));
+        let server_name = ServerName::try_from(server_host.to_string())?;
+
+        let start = Instant::now();
+        let tcp_stream = TcpStream::connect(addr).await?;
+        let connect_cost = start.elapsed();
+
+        let start = Instant::now();
+        let tls_stream = tls_connector.connect(server_name, tcp_stream).await?;
+        let handshake_cost = start.elapsed();
+        let (certs, error) = verifier.take();
+
+        Ok((connect_cost, handshake_cost, tls_stream, certs, error))
+    }
+}
+
+impl PingServer for Tls {
+    fn name(&self) -> &str {
+        "tls"
+    }
+
+    fn ping(&self, host: String, cancell: CancellationToken) -> PingStream {
+        let server = self.clone();
+
+        Box::pin(async_stream::stream! {
+            debug!("start tls request for `{host}`");
+
+            let ret = tokio::select! {
+                ret = server.inspect(&host) => ret,
+                _ = cancell.cancelled() => return,
+            };
+
+            match ret {
+                Ok(task_resp) => {
+                    trace!(
+                        "sending respone ip = `{}`, status = `{}`",
+                        task_resp.ip(),
+                        task_resp.status()
+                    );
+                    yield Ok(task_resp);
+                }
+                Err(e) => yield Err(PingError::from(e)),
+            }
+        })
+    }
+}
@@ -0,0 +1,297 @@
+use std::time::Duration;
+
+use itdog::percentile;
+
+use crate::compare::TOTAL_PHASE;
+use crate::TaskRespone;
+
+/// Header of the summary table, see [`summary_row`].
+pub const SUMMARY_HEADER: [&str; 9] = [
+    "阶段",
+    "最小",
+    "平均",
+    "中位数",
+    "P90",
+    "P95",
+    "P99",
+    "最大",
+    "标准差",
+];
+
+/// The class of status, the negative status such as timeout is failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StatusClass {
+    Success,
+    Redirection,
+    ClientError,
+    ServerError,
+    Failed,
+}
+
+impl StatusClass {
+    pub const ALL: [StatusClass; 5] = [
+        StatusClass::Success,
+        StatusClass::Redirection,
+        StatusClass::ClientError,
+        StatusClass::ServerError,
+        StatusClass::Failed,
+    ];
+
+    pub fn of(status: i32) -> Self {
+        match status {
+            200..=299 => Self::Success,
+            300..=399 => Self::Redirection,
+            400..=499 => Self::ClientError,
+            500..=599 => Self::ServerError,
+            _ => Self::Failed,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusClass::Success => "2xx",
+            StatusClass::Redirection => "3xx",
+            StatusClass::ClientError => "4xx",
+            StatusClass::ServerError => "5xx",
+            StatusClass::Failed => "失败",
+        }
+    }
+}
+
+/// The summary of durations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    count: usize,
+
+    min: Duration,
+
+    avg: Duration,
+
+    median: Duration,
+
+    p90: Duration,
+
+    p95: Duration,
+
+    p99: Duration,
+
+    max: Duration,
+
+    std_dev: Duration,
+}
+
+impl Summary {
+    /// Calculate the summary, `None` if the values is empty.
+    pub fn new(mut values: Vec<Duration>) -> Option<Self> {
+        values.sort();
+
+        let count = values.len();
+        let secs: Vec<f64> = values.iter().map(Duration::as_secs_f64).collect();
+        let avg = secs.iter().sum::<f64>() / count as f64;
+        let variance = secs.iter().map(|v| (v - avg).powi(2)).sum::<f64>() / count as f64;
+
+        Some(Self {
+            count,
+            min: *values.first()?,
+            avg: Duration::try_from_secs_f64(avg).ok()?,
+            median: percentile(&values, 50.0)?,
+            p90: percentile(&values, 90.0)?,
+            p95: percentile(&values, 95.0)?,
+            p99: percentile(&values, 99.0)?,
+            max: *values.last()?,
+            std_dev: Duration::try_from_secs_f64(variance.sqrt()).ok()?,
+        })
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn min(&self) -> Duration {
+        self.min
+    }
+
+    pub fn avg(&self) -> Duration {
+        self.avg
+    }
+
+    pub fn median(&self) -> Duration {
+        self.median
+    }
+
+    pub fn p90(&self) -> Duration {
+        self.p90
+    }
+
+    pub fn p95(&self) -> Duration {
+        self.p95
+    }
+
+    pub fn p99(&self) -> Duration {
+        self.p99
+    }
+
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// The population standard deviation.
+    pub fn std_dev(&self) -> Duration {
+        self.std_dev
+    }
+}
+
+/// The summary of one phase, `None` if there is no successful respone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseStats {
+    name: String,
+
+    summary: Option<Summary>,
+}
+
+impl PhaseStats {
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn summary(&self) -> Option<&Summary> {
+        self.summary.as_ref()
+    }
+}
+
+/// The statistics of respone of a task.
+#[derive(Debug, Clone, Default)]
+pub struct TaskStats {
+    count: usize,
+
+    phases: Vec<PhaseStats>,
+
+    classes: [usize; 5],
+
+    redirects: usize,
+}
+
+impl TaskStats {
+    /// Calculate the statistics, the timing of phases only count the successful respone.
+    pub fn new(respone: &[TaskRespone]) -> Self {
+        let success: Vec<_> = respone
+            .iter()
+            .filter(|v| StatusClass::of(v.status()) == StatusClass::Success)
+            .collect();
+        let mut phases = vec![PhaseStats {
+            name: TOTAL_PHASE.to_string(),
+            summary: Summary::new(
+                success
+                    .iter()
+                    .filter_map(|v| v.total_cost().duration())
+                    .collect(),
+            ),
+        }];
+        let names = respone
+            .first()
+            .map(TaskRespone::other_name_list)
+            .unwrap_or_default();
+
+        for name in names {
+            let values = success
+                .iter()
+                .filter_map(|resp| {
+                    let idx = resp.other_name_list().iter().position(|v| v == name)?;
+
+                    resp.other_cost_list().get(idx)?.duration()
+                })
+                .collect();
+
+            phases.push(PhaseStats {
+                name: name.clone(),
+                summary: Summary::new(values),
+            });
+        }
+
+        let mut classes = [0; 5];
+
+        for resp in respone {
+            classes[StatusClass::of(resp.status()) as usize] += 1;
+        }
+
+        Self {
+            count: respone.len(),
+            phases,
+            classes,
+            redirects: respone
+                .iter()
+                .map(|v| usize::try_from(v.redirect()).unwrap_or_default())
+                .sum(),
+        }
+    }
+
+    /// Count of respone.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The total cost followed by other phases.
+    pub fn phases(&self) -> &[PhaseStats] {
+        self.phases.as_slice()
+    }
+
+    /// Count of respone in the status class.
+    pub fn class_count(&self, class: StatusClass) -> usize {
+        self.classes[class as usize]
+    }
+
+    /// Ratio of respone in the status class, zero if there is no respone.
+    pub fn class_ratio(&self, class: StatusClass) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.class_count(class) as f64 / self.count as f64
+        }
+    }
+
+    /// Sum of redirect count of respone.
+    pub fn redirects(&self) -> usize {
+        self.redirects
+    }
+}
+
+/// Format the summary of phase in seconds, in the order of [`SUMMARY_HEADER`].
+pub fn summary_row(phase: &PhaseStats) -> Vec<String> {
+    let mut row = vec![phase.name().to_string()];
+
+    match phase.summary() {
+        Some(summary) => row.extend(
+            [
+                summary.min(),
+                summary.avg(),
+                summary.median(),
+                summary.p90(),
+                summary.p95(),
+                summary.p99(),
+                summary.max(),
+                summary.std_dev(),
+            ]
+            .map(|v| format!("{:.3}", v.as_secs_f64())),
+        ),
+        None => row.extend(vec!["-".to_string(); SUMMARY_HEADER.len() - 1]),
+    }
+    row
+}
+
+/// Format the status classes such as `2xx 9 (90.0%) | ... | 重定向 2`.
+pub fn class_text(stats: &TaskStats) -> String {
+    let mut parts: Vec<_> = StatusClass::ALL
+        .iter()
+        .map(|class| {
+            format!(
+                "{} {} ({:.1}%)",
+                class.name(),
+                stats.class_count(*class),
+                stats.class_ratio(*class) * 100.0
+            )
+        })
+        .collect();
+
+    parts.push(format!("重定向 {}", stats.redirects()));
+    parts.join(" | ")
+}
@@ -0,0 +1,306 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use itdog::Timing;
+use rusqlite::params;
+use rusqlite::Connection;
+use rusqlite::OptionalExtension;
+use rusqlite::Row;
+use tracing::debug;
+
+use crate::Task;
+use crate::TaskRespone;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS run (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    host        TEXT NOT NULL,
+    server      TEXT NOT NULL,
+    options     TEXT NOT NULL,
+    started_at  INTEGER NOT NULL,
+    ended_at    INTEGER NOT NULL,
+    cancelled   INTEGER NOT NULL,
+    errors      TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS run_host ON run (host, started_at);
+CREATE TABLE IF NOT EXISTS respone (
+    run_id          INTEGER NOT NULL REFERENCES run (id) ON DELETE CASCADE,
+    loc             TEXT NOT NULL,
+    ip              TEXT NOT NULL,
+    status          INTEGER NOT NULL,
+    total_cost      TEXT NOT NULL,
+    total_secs      REAL,
+    redirect        INTEGER NOT NULL,
+    redirect_cost   TEXT NOT NULL,
+    other_names     TEXT NOT NULL,
+    other_costs     TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS respone_run ON respone (run_id);
+";
+
+const RUN_COLUMNS: &str = "
+    run.id, run.host, run.server, run.options, run.started_at, run.ended_at, run.cancelled,
+    run.errors, datetime(run.started_at / 1000, 'unixepoch', 'localtime'),
+    COUNT(respone.run_id),
+    COALESCE(SUM(respone.status >= 200 AND respone.status < 300), 0),
+    AVG(CASE WHEN respone.status >= 200 AND respone.status < 300 THEN respone.total_secs END)
+";
+
+fn to_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|v| v.as_millis().try_into().unwrap_or(i64::MAX))
+        .unwrap_or_default()
+}
+
+fn from_millis(millis: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(millis.try_into().unwrap_or_default())
+}
+
+/// A run of task saved in the history.
+#[derive(Debug, Clone)]
+pub struct RunRecord {
+    id: i64,
+
+    host: String,
+
+    server: String,
+
+    options: String,
+
+    started_at: SystemTime,
+
+    ended_at: SystemTime,
+
+    /// The local time of start, such as `2024-06-01 12:00:00`.
+    started: String,
+
+    cancelled: bool,
+
+    errors: Vec<String>,
+
+    count: usize,
+
+    success: usize,
+
+    avg_total: Option<Duration>,
+}
+
+impl RunRecord {
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        let errors: String = row.get(7)?;
+        let count: i64 = row.get(9)?;
+        let success: i64 = row.get(10)?;
+        let avg_total: Option<f64> = row.get(11)?;
+
+        Ok(Self {
+            id: row.get(0)?,
+            host: row.get(1)?,
+            server: row.get(2)?,
+            options: row.get(3)?,
+            started_at: from_millis(row.get(4)?),
+            ended_at: from_millis(row.get(5)?),
+            cancelled: row.get(6)?,
+            errors: serde_json::from_str(&errors).unwrap_or_default(),
+            started: row.get(8)?,
+            count: count.try_into().unwrap_or_default(),
+            success: success.try_into().unwrap_or_default(),
+            avg_total: avg_total.and_then(|v| Duration::try_from_secs_f64(v).ok()),
+        })
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn host(&self) -> &str {
+        self.host.as_str()
+    }
+
+    pub fn server(&self) -> &str {
+        self.server.as_str()
+    }
+
+    pub fn options(&self) -> &str {
+        self.options.as_str()
+    }
+
+    pub fn started_at(&self) -> SystemTime {
+        self.started_at
+    }
+
+    pub fn ended_at(&self) -> SystemTime {
+        self.ended_at
+    }
+
+    pub fn started(&self) -> &str {
+        self.started.as_str()
+    }
+
+    pub fn cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    pub fn errors(&self) -> &[String] {
+        self.errors.as_slice()
+    }
+
+    /// Count of respone.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Count of respone with 2xx status.
+    pub fn success(&self) -> usize {
+        self.success
+    }
+
+    /// Average total cost of successful respone.
+    pub fn avg_total(&self) -> Option<Duration> {
+        self.avg_total
+    }
+
+    /// The duration of the run.
+    pub fn elapsed(&self) -> Duration {
+        self.ended_at
+            .duration_since(self.started_at)
+            .unwrap_or_default()
+    }
+}
+
+/// The history of the runs, saved in a local SQLite database.
+#[derive(Debug)]
+pub struct Storage {
+    conn: Connection,
+}
+
+impl Storage {
+    /// Open the database, the tables are created if not exist.
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        debug!("open history database `{}`", path.as_ref().display());
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// The default path of database, `$HOME/.httping/history.db`.
+    pub fn default_path() -> PathBuf {
+        std::env::var_os("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join(".httping")
+            .join("history.db")
+    }
+
+    /// Save the task and all the respone of it, return the id of run.
+    pub fn save(&self, task: &Task) -> rusqlite::Result<i64> {
+        let tx = self.conn.unchecked_transaction()?;
+        let errors = serde_json::to_string(task.errors()).unwrap_or_default();
+
+        tx.execute(
+            "INSERT INTO run (host, server, options, started_at, ended_at, cancelled, errors)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                task.host(),
+                task.server(),
+                task.options(),
+                to_millis(task.started_at()),
+                to_millis(task.ended_at().unwrap_or_else(SystemTime::now)),
+                task.cancelled(),
+                errors,
+            ],
+        )?;
+
+        let id = tx.last_insert_rowid();
+
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO respone (run_id, loc, ip, status, total_cost, total_secs, redirect,
+                     redirect_cost, other_names, other_costs)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )?;
+
+            for resp in task.respone() {
+                stmt.execute(params![
+                    id,
+                    resp.loc(),
+                    resp.ip(),
+                    resp.status(),
+                    resp.total_cost().text(),
+                    resp.total_cost().duration().map(|v| v.as_secs_f64()),
+                    resp.redirect(),
+                    resp.redirect_cost().text(),
+                    serde_json::to_string(resp.other_name_list()).unwrap_or_default(),
+                    serde_json::to_string(resp.other_cost_list()).unwrap_or_default(),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        debug!("save run {} of `{}` to history", id, task.host());
+        Ok(id)
+    }
+
+    /// The latest runs, filter by host if given.
+    pub fn runs(&self, host: Option<&str>, limit: usize) -> rusqlite::Result<Vec<RunRecord>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM run LEFT JOIN respone ON respone.run_id = run.id
+             WHERE ?1 IS NULL OR run.host = ?1
+             GROUP BY run.id ORDER BY run.started_at DESC, run.id DESC LIMIT ?2",
+            RUN_COLUMNS
+        ))?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let runs = stmt.query_map(params![host, limit], RunRecord::from_row)?;
+
+        runs.collect()
+    }
+
+    pub fn run(&self, id: i64) -> rusqlite::Result<Option<RunRecord>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM run LEFT JOIN respone ON respone.run_id = run.id
+                     WHERE run.id = ?1 GROUP BY run.id",
+                    RUN_COLUMNS
+                ),
+                params![id],
+                RunRecord::from_row,
+            )
+            .optional()
+    }
+
+    /// All the respone of the run, in the order they are received.
+    pub fn respone(&self, run_id: i64) -> rusqlite::Result<Vec<TaskRespone>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT loc, ip, status, total_cost, redirect, redirect_cost, other_names, other_costs
+             FROM respone WHERE run_id = ?1 ORDER BY rowid",
+        )?;
+        let respone = stmt.query_map(params![run_id], |row| {
+            let total_cost: String = row.get(3)?;
+            let redirect_cost: String = row.get(5)?;
+            let other_names: String = row.get(6)?;
+            let other_costs: String = row.get(7)?;
+
+            Ok(TaskRespone::default()
+                .with_loc(row.get(0)?)
+                .with_ip(row.get(1)?)
+                .with_status(row.get(2)?)
+                .with_total_cost(Timing::new(total_cost))
+                .with_redirect(row.get(4)?)
+                .with_redirect_cost(Timing::new(redirect_cost))
+                .with_other_name_list(serde_json::from_str(&other_names).unwrap_or_default())
+                .with_other_cost_list(serde_json::from_str(&other_costs).unwrap_or_default()))
+        })?;
+
+        respone.collect()
+    }
+}
@@ -19,6 +19,7 @@ pub struct Ui<B: Write> {
 
 impl<B: Write> Ui<B> {
     pub fn new(mut backend: B) -> Result<Self> {
+        Self::install_panic_hook();
         enable_raw_mode()?;
         execute!(backend, EnterAlternateScreen)?;
         Ok(Self {
@@ -58,6 +59,17 @@ impl<B: Write> Ui<B> {
         Ok(())
     }
 
+    /// Restore the terminal before the panic message printed, or it is lost in the alternate screen.
+    fn install_panic_hook() {
+        let hook = std::panic::take_hook();
+
+        std::panic::set_hook(Box::new(move |info| {
+            let _ = disable_raw_mode();
+            let _ = execute!(std::io::stdout(), LeaveAlternateScreen);
+            hook(info);
+        }));
+    }
+
     fn uninit(&mut self) -> Result<()> {
         disable_raw_mode()?;
         execute!(self.terminal.backend_mut(), LeaveAlternateScreen)?;
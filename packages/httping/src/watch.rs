@@ -0,0 +1,137 @@
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use itdog::percentile;
+use tracing::debug;
+
+use crate::PingServer;
+use crate::Task;
+use crate::TaskRespone;
+
+/// The maximum count of rounds kept by [`Watch`], the oldest round is dropped.
+pub const MAX_ROUNDS: usize = 288;
+
+/// The respone of one round of a repeating task.
+#[derive(Debug, Clone)]
+pub struct Round {
+    started_at: SystemTime,
+
+    respone: Vec<TaskRespone>,
+}
+
+impl Round {
+    pub fn new(started_at: SystemTime, respone: Vec<TaskRespone>) -> Self {
+        Self {
+            started_at,
+            respone,
+        }
+    }
+
+    pub fn started_at(&self) -> SystemTime {
+        self.started_at
+    }
+
+    pub fn respone(&self) -> &[TaskRespone] {
+        self.respone.as_slice()
+    }
+
+    /// The sorted total cost of successful respone.
+    pub fn totals(&self) -> Vec<Duration> {
+        let mut totals: Vec<_> = self
+            .respone
+            .iter()
+            .filter(|v| (200..300).contains(&v.status()))
+            .filter_map(|v| v.total_cost().duration())
+            .collect();
+
+        totals.sort();
+        totals
+    }
+
+    pub fn median(&self) -> Option<Duration> {
+        percentile(&self.totals(), 50.0)
+    }
+
+    pub fn p95(&self) -> Option<Duration> {
+        percentile(&self.totals(), 95.0)
+    }
+
+    /// The total cost of the node in this round.
+    pub fn total_of(&self, loc: &str) -> Option<Duration> {
+        self.respone
+            .iter()
+            .find(|v| v.loc() == loc)
+            .and_then(|v| v.total_cost().duration())
+    }
+}
+
+/// Repeat the ping of host on the interval, the respone of each round are kept.
+pub struct Watch {
+    server: Arc<dyn PingServer + Send + Sync>,
+
+    host: String,
+
+    interval: Duration,
+
+    rounds: Vec<Round>,
+
+    next_at: Option<Instant>,
+}
+
+impl Watch {
+    pub fn new(
+        server: Arc<dyn PingServer + Send + Sync>,
+        host: String,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            server,
+            host,
+            interval,
+            rounds: vec![],
+            next_at: None,
+        }
+    }
+
+    pub fn host(&self) -> &str {
+        self.host.as_str()
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub fn rounds(&self) -> &[Round] {
+        self.rounds.as_slice()
+    }
+
+    /// Start a new round.
+    pub fn spawn(&mut self, runtime: &tokio::runtime::Handle) -> Task {
+        debug!("start round {} of `{}`", self.rounds.len() + 1, self.host);
+        self.next_at = None;
+        Task::spawn(runtime, self.server.as_ref(), self.host.clone())
+    }
+
+    /// Keep the respone of finished task as a round, the next round is due after the interval.
+    pub fn finish_round(&mut self, task: &Task) {
+        self.rounds
+            .push(Round::new(task.started_at(), task.respone().to_vec()));
+        if self.rounds.len() > MAX_ROUNDS {
+            self.rounds.remove(0);
+        }
+        self.next_at = Some(Instant::now() + self.interval);
+    }
+
+    /// Return true if the last round is finished and the interval is elapsed.
+    pub fn is_due(&self) -> bool {
+        self.next_at.is_some_and(|v| v <= Instant::now())
+    }
+
+    /// The time until the next round, `None` if the current round is running.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.next_at
+            .map(|v| v.saturating_duration_since(Instant::now()))
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use httping::node_key;
+use httping::Comparison;
+use httping::TaskRespone;
+
+fn respone(loc: &str, status: i32, total: u64, dns: u64) -> TaskRespone {
+    TaskRespone::default()
+        .with_loc(loc.to_string())
+        .with_status(status)
+        .with_total_cost(Duration::from_millis(total))
+        .with_other_name_list(vec!["DNS时间".to_string()])
+        .with_other_cost_list(vec![Duration::from_millis(dns).into()])
+}
+
+#[test]
+fn compare_runs_by_location() {
+    let base = [
+        respone("江苏电信", 200, 100, 10),
+        respone("广东移动", 200, 200, 10),
+        respone("北京联通", 200, 300, 10),
+    ];
+    let current = [
+        respone("广东移动", 502, 200, 10),
+        respone("江苏电信", 200, 110, 20),
+        respone("上海电信", 200, 100, 10),
+    ];
+    let comparison = Comparison::new(&base, &current, 20.0);
+    let nodes = comparison.nodes();
+
+    assert_eq!(
+        nodes.iter().map(|v| v.loc()).collect::<Vec<_>>(),
+        ["江苏电信", "广东移动", "北京联通", "上海电信"]
+    );
+    assert_eq!(comparison.phase_names(), ["总耗时", "DNS时间"]);
+
+    // total is 10% slower, dns is 100% slower
+    let phases = nodes[0].phases();
+
+    assert_eq!(phases[0].percent().map(f64::round), Some(10.0));
+    assert!(!phases[0].is_regression(20.0));
+    assert!(phases[1].is_regression(20.0));
+    assert!(nodes[0].regression());
+    // status failed
+    assert_eq!(nodes[1].current_status(), Some(502));
+    assert!(nodes[1].regression());
+    // missing in current run
+    assert!(nodes[2].current().is_none());
+    assert!(nodes[2].regression());
+    // new node
+    assert!(nodes[3].base().is_none());
+    assert!(!nodes[3].regression());
+    assert_eq!(comparison.regressions().count(), 3);
+}
+
+#[test]
+fn compare_tcping_runs_by_attempt() {
+    let base = [
+        respone("#1 127.0.0.1:51234", 200, 100, 10),
+        respone("#2 127.0.0.1:51236", 200, 100, 10),
+    ];
+    let current = [
+        respone("#1 127.0.0.1:52301", 200, 100, 10),
+        respone("#2 拒绝", -1, 0, 0),
+        respone("#3 127.0.0.1:52305", 200, 100, 10),
+    ];
+    let comparison = Comparison::new(&base, &current, 20.0);
+
+    assert_eq!(node_key("#1 127.0.0.1:51234"), "#1");
+    assert_eq!(node_key("江苏电信"), "江苏电信");
+    assert_eq!(node_key("美国 洛杉矶"), "美国 洛杉矶");
+    assert_eq!(
+        comparison
+            .nodes()
+            .iter()
+            .map(|v| v.loc())
+            .collect::<Vec<_>>(),
+        ["#1", "#2", "#3"]
+    );
+    assert!(comparison.nodes()[0].current().is_some());
+    assert!(!comparison.nodes()[0].regression());
+    assert_eq!(comparison.nodes()[1].current_status(), Some(-1));
+    assert_eq!(comparison.regressions().count(), 1);
+}
@@ -0,0 +1,169 @@
+use std::convert::Infallible;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::net::SocketAddr;
+
+use http::Request;
+use http::Response;
+use http_body_util::Full;
+use httping::Dns;
+use httping::Resolver;
+use httping::Task;
+use httping::STATUS_SUCCESS;
+use hyper::body::Bytes;
+use hyper::body::Incoming;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::net::UdpSocket;
+
+const IPV4: Ipv4Addr = Ipv4Addr::new(10, 0, 0, 1);
+
+const IPV6: Ipv6Addr = Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 1);
+
+/// Answer the A and AAAA query with [`IPV4`] and [`IPV6`].
+fn answer(query: &[u8]) -> Option<Vec<u8>> {
+    // skip the labels of question name
+    let mut end = 12;
+
+    while *query.get(end)? != 0 {
+        end += 1 + *query.get(end)? as usize;
+    }
+
+    let ty = u16::from_be_bytes([*query.get(end + 1)?, *query.get(end + 2)?]);
+    let rdata = match ty {
+        1 => IPV4.octets().to_vec(),
+        28 => IPV6.octets().to_vec(),
+        _ => vec![],
+    };
+    let mut reply = vec![];
+
+    reply.extend_from_slice(&query[0..2]);
+    reply.extend_from_slice(&[0x81, 0x80, 0, 1, 0, u8::from(!rdata.is_empty()), 0, 0, 0, 0]);
+    reply.extend_from_slice(&query[12..end + 5]);
+    if !rdata.is_empty() {
+        // pointer to the question name, type, class, ttl and rdata
+        reply.extend_from_slice(&[0xc0, 0x0c]);
+        reply.extend_from_slice(&ty.to_be_bytes());
+        reply.extend_from_slice(&[0, 1, 0, 0, 0, 60]);
+        reply.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        reply.extend_from_slice(&rdata);
+    }
+    Some(reply)
+}
+
+async fn start_udp_stub() -> std::io::Result<SocketAddr> {
+    let socket = UdpSocket::bind("127.0.0.1:0").await?;
+    let addr = socket.local_addr()?;
+
+    tokio::spawn(async move {
+        let mut buf = [0; 512];
+
+        while let Ok((len, peer)) = socket.recv_from(&mut buf).await {
+            if let Some(reply) = answer(&buf[..len]) {
+                let _ = socket.send_to(&reply, peer).await;
+            }
+        }
+    });
+    Ok(addr)
+}
+
+async fn start_tcp_stub() -> std::io::Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                // the message is prefixed with two bytes length over tcp
+                while let Ok(len) = stream.read_u16().await {
+                    let mut query = vec![0; len as usize];
+
+                    if stream.read_exact(&mut query).await.is_err() {
+                        break;
+                    }
+                    if let Some(reply) = answer(&query) {
+                        let _ = stream.write_u16(reply.len() as u16).await;
+                        let _ = stream.write_all(&reply).await;
+                    }
+                }
+            });
+        }
+    });
+    Ok(addr)
+}
+
+/// Reply the json api of DoH, the answer depends on the type of query.
+async fn doh(req: Request<Incoming>) -> Result<Response<Full<Bytes>>, Infallible> {
+    let query = req.uri().query().unwrap_or_default();
+    let answer = if query.contains("type=AAAA") {
+        format!(r#"{{"type": 28, "data": "{}"}}"#, IPV6)
+    } else {
+        format!(r#"{{"type": 1, "data": "{}"}}"#, IPV4)
+    };
+    let body = format!(r#"{{"Status": 0, "Answer": [{}]}}"#, answer);
+
+    Ok(Response::new(Full::new(Bytes::from(body))))
+}
+
+async fn start_doh_stub() -> std::io::Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let _ = hyper::server::conn::http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service_fn(doh))
+                    .await;
+            });
+        }
+    });
+    Ok(addr)
+}
+
+#[tokio::test]
+async fn lookup_with_stub_resolvers() -> color_eyre::Result<()> {
+    let udp: Resolver = format!("udp://{}", start_udp_stub().await?).parse()?;
+    let tcp: Resolver = format!("tcp://{}", start_tcp_stub().await?).parse()?;
+    let doh: Resolver = format!("http://{}/resolve", start_doh_stub().await?).parse()?;
+    let dns = Dns::default();
+    let expected = [IpAddr::from(IPV4), IpAddr::from(IPV6)];
+
+    for resolver in [udp, tcp, doh] {
+        let mut answers = dns.lookup(&resolver, "www.example.com").await?;
+
+        answers.sort();
+        assert_eq!(answers, expected, "answers of `{}`", resolver);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn ping_with_stub_resolvers() -> color_eyre::Result<()> {
+    let udp: Resolver = format!("udp://{}", start_udp_stub().await?).parse()?;
+    let doh: Resolver = format!("http://{}/resolve", start_doh_stub().await?).parse()?;
+    let dns = Dns::default().with_resolvers(vec![udp.clone(), doh.clone()]);
+    let mut task = Task::spawn(
+        &tokio::runtime::Handle::current(),
+        &dns,
+        "www.example.com".to_string(),
+    );
+
+    task.wait().await;
+
+    let respone = task.respone();
+
+    assert_eq!(respone.len(), 2);
+    assert_eq!(respone[0].loc(), udp.to_string());
+    assert_eq!(respone[1].loc(), doh.to_string());
+    for resp in respone {
+        assert_eq!(resp.status(), STATUS_SUCCESS);
+        // the answers are comparable across resolvers
+        assert_eq!(resp.ip(), "10.0.0.1, fd00::1");
+    }
+    Ok(())
+}
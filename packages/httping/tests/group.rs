@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use httping::group_respone;
+use httping::GroupBy;
+use httping::TaskRespone;
+
+fn respone(loc: &str, status: i32, total: u64) -> TaskRespone {
+    TaskRespone::default()
+        .with_loc(loc.to_string())
+        .with_status(status)
+        .with_total_cost(Duration::from_millis(total))
+}
+
+#[test]
+fn group_respone_by_location() {
+    let respone = [
+        respone("江苏电信", 200, 100),
+        respone("广东移动", -2, 0),
+        respone("江苏移动", 502, 900),
+        respone("广东电信", 200, 300),
+        respone("本机", 200, 10),
+    ];
+    let groups = group_respone(&respone, GroupBy::Isp);
+
+    assert_eq!(
+        groups.iter().map(|v| v.name()).collect::<Vec<_>>(),
+        ["电信", "移动", "未知"]
+    );
+    assert_eq!(groups[0].success(), 2);
+    assert_eq!(groups[0].summary().unwrap().avg().as_millis(), 200);
+    // the carrier is down
+    assert_eq!(groups[1].count(), 2);
+    assert_eq!(groups[1].success_ratio(), 0.0);
+    assert!(groups[1].summary().is_none());
+
+    let groups = group_respone(&respone, GroupBy::Province);
+
+    assert_eq!(
+        groups.iter().map(|v| v.name()).collect::<Vec<_>>(),
+        ["江苏", "广东", "未知"]
+    );
+    assert_eq!(groups[0].success_ratio(), 0.5);
+    assert_eq!(groups[1].count(), 2);
+}
@@ -0,0 +1,63 @@
+use futures_util::StreamExt;
+use httping::Itdog;
+use httping::PingServer;
+use itdog::mock::MockServer;
+use itdog::ItdogClient;
+use tokio_util::sync::CancellationToken;
+
+#[tokio::test]
+async fn itdog_ping_with_mock() -> color_eyre::Result<()> {
+    let server = MockServer::builder()
+        .with_message(MockServer::node("江苏电信", "1.1.1.1", 200, "0.123"))
+        .with_message(MockServer::node("广东移动", "2.2.2.2", 502, "1.234"))
+        .start()
+        .await?;
+    let itdog = Itdog::new(
+        ItdogClient::builder()
+            .with_endpoint(server.endpoint())
+            .build()?,
+    );
+    let resps = itdog
+        .ping("www.example.com".to_string(), CancellationToken::new())
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(resps.len(), 2);
+
+    let first = resps[0].as_ref().unwrap();
+    let second = resps[1].as_ref().unwrap();
+
+    assert_eq!(first.loc(), "江苏电信");
+    assert_eq!(first.status(), 200);
+    assert_eq!(first.total_cost().text(), "0.123");
+    assert_eq!(first.total_cost().as_millis(), 123);
+    assert_eq!(
+        first
+            .other_cost_list()
+            .iter()
+            .map(|v| v.as_millis())
+            .collect::<Vec<_>>(),
+        [10, 20, 30]
+    );
+    assert_eq!(second.loc(), "广东移动");
+    assert_eq!(second.status(), 502);
+    Ok(())
+}
+
+#[tokio::test]
+async fn itdog_ping_error_in_stream() -> color_eyre::Result<()> {
+    let server = MockServer::builder().with_task_id(None).start().await?;
+    let itdog = Itdog::new(
+        ItdogClient::builder()
+            .with_endpoint(server.endpoint())
+            .build()?,
+    );
+    let resps = itdog
+        .ping("www.example.com".to_string(), CancellationToken::new())
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(resps.len(), 1);
+    assert!(resps[0].is_err());
+    Ok(())
+}
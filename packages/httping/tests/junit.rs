@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use httping::junit_report;
+use httping::PingError;
+use httping::Task;
+use httping::TaskRespone;
+use itdog::Thresholds;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+fn respone(loc: &str, status: i32, total: u64) -> TaskRespone {
+    TaskRespone::default()
+        .with_loc(loc.to_string())
+        .with_ip("127.0.0.1".to_string())
+        .with_status(status)
+        .with_total_cost(Duration::from_millis(total))
+}
+
+/// Create a finished task received the respone and errors.
+async fn task(host: &str, items: Vec<Result<TaskRespone, PingError>>) -> Task {
+    let (tx, rx) = mpsc::channel(items.len().max(1));
+    let handler = tokio::spawn(async move {
+        for item in items {
+            let _ = tx.send(item).await;
+        }
+    });
+    let mut task = Task::new(host.to_string(), handler, CancellationToken::new(), rx);
+
+    task.wait().await;
+    task
+}
+
+#[tokio::test]
+async fn report_failures_and_errors() -> color_eyre::Result<()> {
+    let tasks = [
+        task(
+            "www.baidu.com",
+            vec![
+                Ok(respone("江苏电信", 200, 100)),
+                Ok(respone("广东移动", 301, 100)),
+                Ok(respone("北京联通", 200, 900)),
+            ],
+        )
+        .await,
+        task(
+            "www.qq.com",
+            vec![Err(PingError::Other("connection refused".to_string()))],
+        )
+        .await,
+    ];
+    let thresholds = Thresholds::default().with_max_total(Some(Duration::from_millis(500)));
+    let xml = junit_report(&tasks, &[thresholds.clone(), thresholds])?;
+
+    assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+    assert!(xml.contains(r#"<testsuites name="httping" tests="4" failures="2" errors="1""#));
+    assert!(xml.contains(r#"<testsuite name="www.baidu.com" tests="3" failures="2" errors="0""#));
+    // 2xx passed
+    assert!(xml.contains(r#"<testcase name="江苏电信" classname="www.baidu.com" time="0.100"/>"#));
+    // 3xx is failed even if no expected status given
+    assert!(xml.contains(r#"<failure message="广东移动: unexpected status 301""#));
+    assert!(xml.contains(r#"<failure message="北京联通: total cost 0.900s exceeds 0.500s""#));
+    assert!(xml.contains(r#"<testsuite name="www.qq.com" tests="1" failures="0" errors="1""#));
+    assert!(xml.contains(r#"<error message="connection refused" type="error"/>"#));
+    Ok(())
+}
+
+#[tokio::test]
+async fn report_with_expected_status() -> color_eyre::Result<()> {
+    let tasks = [task(
+        "www.baidu.com",
+        vec![
+            Ok(respone("江苏电信", 200, 100)),
+            Ok(respone("广东移动", 301, 100)),
+        ],
+    )
+    .await];
+    let thresholds = Thresholds::default().with_expect_status(vec![301]);
+    let xml = junit_report(&tasks, &[thresholds])?;
+
+    assert!(xml.contains(r#"<failure message="江苏电信: unexpected status 200""#));
+    assert!(xml.contains(r#"<testcase name="广东移动" classname="www.baidu.com" time="0.100"/>"#));
+    Ok(())
+}
+
+#[tokio::test]
+async fn report_escape_text() -> color_eyre::Result<()> {
+    let tasks = [task(
+        "http://a.com/?x=1&y=\"<2>\"",
+        vec![Ok(respone("it's\u{7}", 200, 100))],
+    )
+    .await];
+    let xml = junit_report(&tasks, &[Thresholds::default()])?;
+
+    assert!(xml.contains(r#"<testsuite name="http://a.com/?x=1&amp;y=&quot;&lt;2&gt;&quot;""#));
+    // the control character is removed
+    assert!(xml.contains(r#"<testcase name="it&apos;s" "#));
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use http::Method;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+use http_body_util::Full;
+use httping::LocalHttp;
+use httping::Task;
+use hyper::body::Bytes;
+use hyper::body::Incoming;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+
+/// Reply `pong` to GET, and 204 to other methods.
+async fn pong(req: Request<Incoming>) -> Result<Response<Full<Bytes>>, Infallible> {
+    let mut resp = Response::new(Full::new(Bytes::from("pong")));
+
+    if req.method() != Method::GET {
+        *resp.status_mut() = StatusCode::NO_CONTENT;
+        *resp.body_mut() = Full::default();
+    }
+    Ok(resp)
+}
+
+async fn start_server(addr: &str) -> std::io::Result<SocketAddr> {
+    let listener = TcpListener::bind(addr).await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let _ = hyper::server::conn::http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service_fn(pong))
+                    .await;
+            });
+        }
+    });
+    Ok(addr)
+}
+
+#[tokio::test]
+async fn probe_local_server() -> color_eyre::Result<()> {
+    let addr = start_server("127.0.0.1:0").await?;
+    let resp = LocalHttp::default()
+        .probe(&format!("http://127.0.0.1:{}/ping", addr.port()))
+        .await?;
+
+    assert_eq!(resp.loc(), "本机");
+    assert_eq!(resp.ip(), "127.0.0.1");
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.other_name_list(),
+        [
+            "DNS时间",
+            "连接时间",
+            "TLS握手时间",
+            "首字节时间",
+            "下载时间"
+        ]
+    );
+    // no tls handshake for http
+    assert_eq!(resp.other_cost_list()[2].duration(), Some(Duration::ZERO));
+    assert!(resp.total_cost().duration().is_some());
+
+    let resp = LocalHttp::default()
+        .with_method(Method::HEAD)
+        .probe(&format!("http://127.0.0.1:{}", addr.port()))
+        .await?;
+
+    assert_eq!(resp.status(), 204);
+    Ok(())
+}
+
+#[tokio::test]
+async fn probe_local_server_with_ipv6() -> color_eyre::Result<()> {
+    // skip if the ipv6 loopback is not available
+    let Ok(addr) = start_server("[::1]:0").await else {
+        return Ok(());
+    };
+    let resp = LocalHttp::default()
+        .probe(&format!("http://[::1]:{}/", addr.port()))
+        .await?;
+
+    assert_eq!(resp.ip(), "::1");
+    assert_eq!(resp.status(), 200);
+    Ok(())
+}
+
+#[tokio::test]
+async fn ping_local_server_in_task() -> color_eyre::Result<()> {
+    let addr = start_server("127.0.0.1:0").await?;
+    let local = LocalHttp::default().with_timeout(Duration::from_secs(5));
+    let mut task = Task::spawn(
+        &tokio::runtime::Handle::current(),
+        &local,
+        format!("http://127.0.0.1:{}", addr.port()),
+    );
+
+    task.wait().await;
+    assert!(task.errors().is_empty());
+    assert_eq!(task.respone().len(), 1);
+    assert_eq!(task.respone()[0].status(), 200);
+    Ok(())
+}
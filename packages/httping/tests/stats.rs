@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use httping::StatusClass;
+use httping::Summary;
+use httping::TaskRespone;
+use httping::TaskStats;
+
+fn respone(status: i32, total: u64, dns: u64, redirect: i32) -> TaskRespone {
+    TaskRespone::default()
+        .with_status(status)
+        .with_total_cost(Duration::from_millis(total))
+        .with_redirect(redirect)
+        .with_other_name_list(vec!["DNS时间".to_string()])
+        .with_other_cost_list(vec![Duration::from_millis(dns).into()])
+}
+
+#[test]
+fn summary_of_durations() {
+    let summary = Summary::new([400, 100, 300, 200].map(Duration::from_millis).to_vec()).unwrap();
+
+    assert_eq!(summary.count(), 4);
+    assert_eq!(summary.min(), Duration::from_millis(100));
+    assert_eq!(summary.max(), Duration::from_millis(400));
+    assert_eq!(summary.avg().as_millis(), 250);
+    assert_eq!(summary.median(), Duration::from_millis(200));
+    assert_eq!(summary.p90(), Duration::from_millis(400));
+    assert_eq!(summary.std_dev().as_micros(), 111_803);
+    assert!(Summary::new(vec![]).is_none());
+}
+
+#[test]
+fn task_stats_of_respone() {
+    let stats = TaskStats::new(&[
+        respone(200, 100, 10, 0),
+        respone(200, 300, 30, 2),
+        respone(301, 50, 5, 1),
+        respone(502, 900, 90, 0),
+        respone(-2, 0, 0, 0),
+    ]);
+    let phases = stats.phases();
+
+    assert_eq!(stats.count(), 5);
+    assert_eq!(
+        phases.iter().map(|v| v.name()).collect::<Vec<_>>(),
+        ["总耗时", "DNS时间"]
+    );
+    // only the successful respone are counted
+    assert_eq!(phases[0].summary().unwrap().count(), 2);
+    assert_eq!(
+        phases[0].summary().unwrap().avg(),
+        Duration::from_millis(200)
+    );
+    assert_eq!(
+        phases[1].summary().unwrap().max(),
+        Duration::from_millis(30)
+    );
+    assert_eq!(stats.class_count(StatusClass::Success), 2);
+    assert_eq!(stats.class_count(StatusClass::Redirection), 1);
+    assert_eq!(stats.class_count(StatusClass::ClientError), 0);
+    assert_eq!(stats.class_count(StatusClass::ServerError), 1);
+    assert_eq!(stats.class_count(StatusClass::Failed), 1);
+    assert_eq!(stats.class_ratio(StatusClass::Success), 0.4);
+    assert_eq!(stats.redirects(), 3);
+}
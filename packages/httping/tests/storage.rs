@@ -0,0 +1,55 @@
+use httping::Itdog;
+use httping::Storage;
+use httping::Task;
+use itdog::mock::MockServer;
+use itdog::ItdogClient;
+
+#[tokio::test]
+async fn save_and_load_history() -> color_eyre::Result<()> {
+    let server = MockServer::builder()
+        .with_message(MockServer::node("江苏电信", "1.1.1.1", 200, "0.100"))
+        .with_message(MockServer::node("广东移动", "2.2.2.2", 502, "1.234"))
+        .with_message(MockServer::node("北京联通", "3.3.3.3", 200, "0.300"))
+        .start()
+        .await?;
+    let itdog = Itdog::new(
+        ItdogClient::builder()
+            .with_endpoint(server.endpoint())
+            .build()?,
+    );
+    let mut task = Task::spawn(
+        &tokio::runtime::Handle::current(),
+        &itdog,
+        "www.example.com".to_string(),
+    );
+
+    task.wait().await;
+
+    let storage = Storage::open_in_memory()?;
+    let id = storage.save(&task)?;
+    let runs = storage.runs(Some("www.example.com"), 10)?;
+
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].id(), id);
+    assert_eq!(runs[0].server(), "itdog");
+    assert_eq!(runs[0].count(), 3);
+    assert_eq!(runs[0].success(), 2);
+    assert_eq!(runs[0].avg_total().map(|v| v.as_millis()), Some(200));
+    assert!(storage.runs(Some("www.baidu.com"), 10)?.is_empty());
+
+    let respone = storage.respone(id)?;
+
+    assert_eq!(respone.len(), 3);
+    assert_eq!(respone[1].loc(), "广东移动");
+    assert_eq!(respone[1].status(), 502);
+    assert_eq!(respone[1].total_cost().text(), "1.234");
+    assert_eq!(
+        respone[1]
+            .other_cost_list()
+            .iter()
+            .map(|v| v.as_millis())
+            .collect::<Vec<_>>(),
+        [10, 20, 30]
+    );
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use httping::Tls;
+use httping::STATUS_FAILED;
+use rcgen::date_time_ymd;
+use rcgen::CertificateParams;
+use rcgen::DnType;
+use rcgen::KeyPair;
+use rustls::pki_types::PrivateKeyDer;
+use rustls::pki_types::PrivatePkcs8KeyDer;
+use rustls::ServerConfig;
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+
+/// Start a tls server with self signed certificate valid from `not_before` to `not_after` year.
+async fn start_server(not_before: i32, not_after: i32) -> color_eyre::Result<SocketAddr> {
+    let key_pair = KeyPair::generate()?;
+    let mut params = CertificateParams::new(vec!["127.0.0.1".to_string()])?;
+
+    params
+        .distinguished_name
+        .push(DnType::CommonName, "httping test");
+    params.not_before = date_time_ymd(not_before, 1, 1);
+    params.not_after = date_time_ymd(not_after, 1, 1);
+
+    let cert = params.self_signed(&key_pair)?;
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key_pair.serialize_der()));
+    let config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert.der().clone()], key)?;
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+
+            tokio::spawn(async move {
+                let _ = acceptor.accept(stream).await;
+            });
+        }
+    });
+    Ok(addr)
+}
+
+fn info<'a>(info: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    info.iter()
+        .find(|(v, _)| v == name)
+        .map(|(_, value)| value.as_str())
+}
+
+#[tokio::test]
+async fn inspect_expired_certificate() -> color_eyre::Result<()> {
+    let addr = start_server(1999, 2000).await?;
+    let resp = Tls::default()
+        .inspect(&format!("127.0.0.1:{}", addr.port()))
+        .await?;
+
+    assert_eq!(resp.status(), STATUS_FAILED);
+    // only the timing are in the cost list
+    assert_eq!(resp.other_name_list(), ["连接时间", "握手时间"]);
+    assert!(resp
+        .other_cost_list()
+        .iter()
+        .all(|v| v.duration().is_some()));
+    assert!(info(resp.other_info(), "证书验证").is_some_and(|v| v.starts_with("失败")));
+    assert!(info(resp.other_info(), "httping test 到期")
+        .is_some_and(|v| v.starts_with("2000-01-01 (-")));
+    Ok(())
+}
+
+#[tokio::test]
+async fn inspect_untrusted_certificate() -> color_eyre::Result<()> {
+    let addr = start_server(2000, 2100).await?;
+    let resp = Tls::default()
+        .inspect(&format!("127.0.0.1:{}", addr.port()))
+        .await?;
+
+    // the chain is inspected even if the certificate is not trusted
+    assert_eq!(resp.status(), STATUS_FAILED);
+    assert!(info(resp.other_info(), "证书验证").is_some_and(|v| v.starts_with("失败")));
+    assert!(info(resp.other_info(), "httping test 到期")
+        .is_some_and(|v| v.starts_with("2100-01-01 (") && !v.contains("(-")));
+    assert!(info(resp.other_info(), "协议版本").is_some_and(|v| v.contains("TLS")));
+    Ok(())
+}
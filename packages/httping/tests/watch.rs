@@ -0,0 +1,34 @@
+use std::time::Duration;
+use std::time::SystemTime;
+
+use httping::Round;
+use httping::TaskRespone;
+
+fn respone(loc: &str, status: i32, total: u64) -> TaskRespone {
+    TaskRespone::default()
+        .with_loc(loc.to_string())
+        .with_status(status)
+        .with_total_cost(Duration::from_millis(total))
+}
+
+#[test]
+fn round_median_and_p95() {
+    let round = Round::new(
+        SystemTime::now(),
+        vec![
+            respone("江苏电信", 200, 300),
+            respone("广东移动", 200, 100),
+            respone("北京联通", 502, 900),
+            respone("上海电信", 200, 200),
+            respone("浙江联通", 200, 400),
+        ],
+    );
+
+    // the failed respone is ignored
+    assert_eq!(round.totals().len(), 4);
+    assert_eq!(round.median(), Some(Duration::from_millis(200)));
+    assert_eq!(round.p95(), Some(Duration::from_millis(400)));
+    assert_eq!(round.total_of("北京联通"), Some(Duration::from_millis(900)));
+    assert_eq!(round.total_of("香港"), None);
+    assert_eq!(Round::new(SystemTime::now(), vec![]).median(), None);
+}
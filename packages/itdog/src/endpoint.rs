@@ -0,0 +1,112 @@
+use std::io::BufReader;
+use std::path::Path;
+
+use rustls::pki_types::CertificateDer;
+use rustls::RootCertStore;
+
+use crate::Error;
+
+pub const DEFAULT_HTTP_URL: &str = "https://www.itdog.cn/http/";
+
+pub const DEFAULT_WS_URL: &str = "wss://www.itdog.cn/websockets";
+
+/// The endpoint of itdog, it can be pointed to a mirror, proxy or a local mock server.
+#[derive(Debug, Clone)]
+pub struct Endpoint {
+    http_url: String,
+
+    ws_url: String,
+
+    tls: bool,
+
+    root_certs: Vec<CertificateDer<'static>>,
+}
+
+impl Default for Endpoint {
+    fn default() -> Self {
+        Self::new(DEFAULT_HTTP_URL, DEFAULT_WS_URL)
+    }
+}
+
+impl Endpoint {
+    /// Create an endpoint, the tls is enabled if the scheme of `ws_url` is `wss`.
+    pub fn new(http_url: impl Into<String>, ws_url: impl Into<String>) -> Self {
+        let ws_url = ws_url.into();
+
+        Self {
+            http_url: http_url.into(),
+            tls: ws_url.starts_with("wss://"),
+            ws_url,
+            root_certs: vec![],
+        }
+    }
+
+    pub fn with_http_url(mut self, http_url: impl Into<String>) -> Self {
+        self.http_url = http_url.into();
+        self
+    }
+
+    pub fn with_ws_url(mut self, ws_url: impl Into<String>) -> Self {
+        self.ws_url = ws_url.into();
+        self
+    }
+
+    pub fn with_tls(mut self, tls: bool) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Add the certificates trusted in addition to the webpki roots.
+    pub fn with_root_certs(mut self, root_certs: Vec<CertificateDer<'static>>) -> Self {
+        self.root_certs.extend(root_certs);
+        self
+    }
+
+    /// Load the certificates from the PEM file and trust them.
+    pub fn with_root_certs_pem(self, path: impl AsRef<Path>) -> crate::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let certs = rustls_pemfile::certs(&mut BufReader::new(file)).collect::<Result<_, _>>()?;
+
+        Ok(self.with_root_certs(certs))
+    }
+
+    pub fn http_url(&self) -> &str {
+        self.http_url.as_str()
+    }
+
+    pub fn ws_url(&self) -> &str {
+        self.ws_url.as_str()
+    }
+
+    pub fn tls(&self) -> bool {
+        self.tls
+    }
+
+    pub fn root_certs(&self) -> &[CertificateDer<'static>] {
+        self.root_certs.as_slice()
+    }
+
+    /// Return the host and port of websocket server.
+    pub fn ws_server(&self) -> crate::Result<(String, u16)> {
+        let uri = self
+            .ws_url
+            .parse::<http::Uri>()
+            .map_err(|e| Error::invalid_endpoint(format!("`{}`: {}", self.ws_url, e)))?;
+        let host = uri.host().ok_or_else(|| {
+            Error::invalid_endpoint(format!("can not find host in `{}`", self.ws_url))
+        })?;
+        let port = uri.port_u16().unwrap_or(if self.tls { 443 } else { 80 });
+
+        Ok((host.to_string(), port))
+    }
+
+    /// Return the webpki roots and the root certificates of endpoint.
+    pub fn root_store(&self) -> crate::Result<RootCertStore> {
+        let mut store = RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+
+        for cert in self.root_certs.iter() {
+            store.add(cert.clone())?;
+        }
+        Ok(store)
+    }
+}
@@ -0,0 +1,89 @@
+use fastwebsockets::WebSocketError;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("can not find task_id in result page")]
+    TaskIdNotFound,
+
+    #[error("out of range, md5 string = `{0}`")]
+    Md5Range(String),
+
+    #[error("invalid parameter: {0}")]
+    InvalidParam(String),
+
+    #[error("invalid endpoint: {0}")]
+    InvalidEndpoint(String),
+
+    #[error("http request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("tls error: {0}")]
+    Tls(#[from] rustls::Error),
+
+    #[error("websocket handshake failed: {0}")]
+    Handshake(WebSocketError),
+
+    #[error("websocket error: {0}")]
+    WebSocket(WebSocketError),
+
+    #[error("malformed message `{text}`: {source}")]
+    MalformedMessage {
+        text: String,
+
+        source: serde_json::Error,
+    },
+
+    #[error("invalid utf-8 message: {0}")]
+    InvalidUtf8(#[from] std::string::FromUtf8Error),
+
+    #[error("receiver of message is closed")]
+    ReceiverClosed,
+
+    #[error("query is cancelled")]
+    Cancelled,
+
+    #[error("{0} timed out")]
+    Timeout(&'static str),
+}
+
+impl Error {
+    pub fn invalid_param(msg: impl Into<String>) -> Self {
+        Self::InvalidParam(msg.into())
+    }
+
+    pub fn invalid_endpoint(msg: impl Into<String>) -> Self {
+        Self::InvalidEndpoint(msg.into())
+    }
+
+    /// Return true if the error may disappear after retry, such as network errors and timeouts.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::TaskIdNotFound
+            | Self::Io(_)
+            | Self::Tls(_)
+            | Self::Handshake(_)
+            | Self::Timeout(_) => true,
+            Self::Http(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            Self::WebSocket(e) => matches!(
+                e,
+                WebSocketError::ConnectionClosed
+                    | WebSocketError::UnexpectedEOF
+                    | WebSocketError::IoError(_)
+            ),
+            _ => false,
+        }
+    }
+
+    /// Convert the io error of tls stream, keep the rustls error if any.
+    pub fn from_tls_io(e: std::io::Error) -> Self {
+        match e.get_ref().and_then(|v| v.downcast_ref::<rustls::Error>()) {
+            Some(tls_error) => Self::Tls(tls_error.clone()),
+            None => Self::Io(e),
+        }
+    }
+}
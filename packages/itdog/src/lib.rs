@@ -1,4 +1,17 @@
+#[cfg(feature = "mock")]
+pub mod mock;
+
+mod endpoint;
+mod error;
+mod location;
+mod options;
+mod output;
+mod param;
+mod policy;
 mod req;
+mod target;
+mod threshold;
+mod timing;
 
 use std::sync::Arc;
 
@@ -11,90 +24,239 @@ use reqwest::header::SEC_WEBSOCKET_KEY;
 use reqwest::header::SEC_WEBSOCKET_VERSION;
 use reqwest::header::UPGRADE;
 use rustls::ClientConfig;
-use rustls::RootCertStore;
 use std::future::Future;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
 use tokio::net::TcpStream;
 use tokio::sync::mpsc::Sender;
-use tokio::sync::oneshot::Receiver;
 use tokio_rustls::TlsConnector;
+use tokio_util::sync::CancellationToken;
 use tracing::debug;
 use tracing::trace;
 
 pub const DEFAULT_KEY: &str = "token_20230313000136kwyktxb0tgspm00yo5";
 
+pub use endpoint::Endpoint;
+pub use endpoint::DEFAULT_HTTP_URL;
+pub use endpoint::DEFAULT_WS_URL;
+pub use error::Error;
+pub use error::Result;
+pub use location::Location;
+pub use options::QueryOptions;
+pub use output::csv_line;
+pub use output::OutputFormat;
+pub use param::CheckMode;
+pub use param::DnsServerType;
+pub use param::HttpMethod;
+pub use param::Line;
+pub use policy::parse_duration;
+pub use policy::RetryPolicy;
+pub use policy::Timeouts;
 pub use req::Message;
 pub use req::ReqClient;
+pub use target::parse_targets;
+pub use target::read_targets;
+pub use target::Target;
+pub use threshold::parse_status_list;
+pub use threshold::percentile;
+pub use threshold::Sample;
+pub use threshold::Thresholds;
+pub use threshold::Violation;
+pub use threshold::EXIT_VIOLATION;
+pub use timing::Timing;
 
-#[derive(Debug)]
-pub struct ItdogClient<'a> {
-    key: &'a str,
+#[derive(Debug, Default)]
+pub struct ItdogClientBuilder {
+    key: Option<String>,
 
-    host: &'a str,
+    cli: Option<reqwest::Client>,
 
-    cancell: Receiver<bool>,
+    endpoint: Option<Endpoint>,
 
-    respone: Sender<req::Message>,
+    timeouts: Option<Timeouts>,
+
+    retry: Option<RetryPolicy>,
 }
 
-macro_rules! return_if_cancell {
-    ($_self:ident) => {
-        if let Ok(flag) = $_self.cancell.try_recv() {
-            if flag {
-                return Ok(());
+impl ItdogClientBuilder {
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Use the given client instead of creating a new one, the client should enable cookie store.
+    ///
+    /// The root certificates of endpoint are not added to the given client.
+    pub fn with_client(mut self, cli: reqwest::Client) -> Self {
+        self.cli = Some(cli);
+        self
+    }
+
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = Some(timeouts);
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    pub fn build(self) -> Result<ItdogClient> {
+        let endpoint = self.endpoint.unwrap_or_default();
+        let cli = match self.cli {
+            Some(cli) => cli,
+            None => {
+                let mut builder = reqwest::ClientBuilder::new().cookie_store(true);
+
+                for cert in endpoint.root_certs() {
+                    builder = builder.add_root_certificate(reqwest::Certificate::from_der(cert)?);
+                }
+                builder.build()?
             }
+        };
+
+        Ok(ItdogClient {
+            key: self.key.unwrap_or_else(|| DEFAULT_KEY.to_string()),
+            cli,
+            endpoint,
+            timeouts: self.timeouts.unwrap_or_default(),
+            retry: self.retry.unwrap_or_default(),
+        })
+    }
+}
+
+/// The client of itdog, it can be cloned and shared between queries.
+#[derive(Debug, Clone)]
+pub struct ItdogClient {
+    key: String,
+
+    cli: reqwest::Client,
+
+    endpoint: Endpoint,
+
+    timeouts: Timeouts,
+
+    retry: RetryPolicy,
+}
+
+/// Wait the future, return [`Error::Cancelled`] if the token is cancelled before it finished.
+macro_rules! cancellable {
+    ($cancell:ident, $fut:expr) => {
+        tokio::select! {
+            ret = $fut => ret,
+            _ = $cancell.cancelled() => return Err(Error::Cancelled),
         }
     };
 }
 
-impl<'a> ItdogClient<'a> {
-    pub fn new(
-        key: &'a str,
-        host: &'a str,
-        cancell: Receiver<bool>,
-        respone: Sender<req::Message>,
-    ) -> Self {
-        Self {
-            key,
-            host,
-            cancell,
-            respone,
-        }
+impl ItdogClient {
+    pub fn builder() -> ItdogClientBuilder {
+        ItdogClientBuilder::default()
     }
 
-    pub async fn query(&mut self) -> color_eyre::Result<()> {
-        let cli = reqwest::ClientBuilder::new().cookie_store(true).build()?;
-        let server_host = "www.itdog.cn";
+    pub fn key(&self) -> &str {
+        self.key.as_str()
+    }
 
-        debug!("try to httping host `{}`", self.host);
-        return_if_cancell!(self);
+    pub fn cli(&self) -> &reqwest::Client {
+        &self.cli
+    }
 
-        let reqc = req::ReqClient::new(cli, self.key, self.host);
-        let pingmsg = reqc.req_wssocket_msg("https://www.itdog.cn/http/").await?;
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
 
-        debug!("construct ping message `{pingmsg}`");
-        return_if_cancell!(self);
+    pub fn timeouts(&self) -> &Timeouts {
+        &self.timeouts
+    }
+
+    pub fn retry(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// Query the `host` with `options`, send the [`Message`] of each node to `respone`.
+    ///
+    /// Return [`Error::Cancelled`] if `cancell` is cancelled, the websocket is closed before return.
+    /// The query is retried by the [`RetryPolicy`] if it failed with transient error before
+    /// any message is sent.
+    pub async fn query(
+        &self,
+        host: &str,
+        options: &QueryOptions,
+        cancell: CancellationToken,
+        respone: Sender<req::Message>,
+    ) -> Result<()> {
+        let query = async {
+            let mut retry = 0;
+
+            loop {
+                let mut forwarded = 0;
+                let ret = self
+                    .query_once(host, options, &cancell, &respone, &mut forwarded)
+                    .await;
+
+                match ret {
+                    // the nodes will be duplicated if retry after message sent
+                    Err(e)
+                        if e.is_transient()
+                            && forwarded == 0
+                            && retry < self.retry.max_retries() =>
+                    {
+                        let backoff = self.retry.backoff(retry);
+
+                        debug!("query failed: {e}, retry after {backoff:?}");
+                        retry += 1;
+                        cancellable!(cancell, tokio::time::sleep(backoff));
+                    }
+                    ret => return ret,
+                }
+            }
+        };
+
+        policy::timeout(self.timeouts.overall(), "query", query).await
+    }
 
-        // Prepare a tls connection
-        let tcp_stream = TcpStream::connect(&format!("{}:443", server_host)).await?;
-        let config = ClientConfig::builder()
-            .with_root_certificates(RootCertStore::from_iter(
-                webpki_roots::TLS_SERVER_ROOTS.iter().cloned(),
-            ))
-            .with_no_client_auth();
-        let tls_connector = TlsConnector::from(Arc::new(config));
+    async fn query_once(
+        &self,
+        host: &str,
+        options: &QueryOptions,
+        cancell: &CancellationToken,
+        respone: &Sender<req::Message>,
+        forwarded: &mut usize,
+    ) -> Result<()> {
+        let (server_host, server_port) = self.endpoint.ws_server()?;
+        let connect_timeout = self.timeouts.connect();
 
-        debug!("construct tls connector");
-        return_if_cancell!(self);
+        debug!("try to httping host `{}`", host);
 
-        let server_name =
-            tokio_rustls::rustls::pki_types::ServerName::try_from(server_host)?.to_owned();
-        let tls_stream = tls_connector.connect(server_name, tcp_stream).await?;
+        let reqc = req::ReqClient::new(self.cli.clone(), self.key.as_str(), host)
+            .with_options(options)
+            .with_timeout(connect_timeout);
+        let pingmsg = cancellable!(cancell, reqc.req_wssocket_msg(self.endpoint.http_url()))?;
+
+        debug!("construct ping message `{pingmsg}`");
+
+        let tcp_stream = cancellable!(
+            cancell,
+            policy::timeout(connect_timeout, "connect", async {
+                Ok(TcpStream::connect((server_host.as_str(), server_port)).await?)
+            })
+        )?;
 
         // Prepare a request
         let request = reqc
             .cli()
-            .get("wss://www.itdog.cn/websockets")
-            .header(HOST, server_host)
+            .get(self.endpoint.ws_url())
+            .header(
+                HOST,
+                Self::host_header(&server_host, server_port, self.endpoint.tls()),
+            )
             .header(UPGRADE, "websocket")
             .header(CONNECTION, "upgrade")
             .header(SEC_WEBSOCKET_KEY, fastwebsockets::handshake::generate_key())
@@ -105,9 +267,64 @@ impl<'a> ItdogClient<'a> {
         let request = http::Request::from_parts(parts, String::default());
 
         debug!("construct http request: `{request:?}`");
-        return_if_cancell!(self);
 
-        let (websocket, _) = handshake::client(&SpawnExecutor, request, tls_stream).await?;
+        if self.endpoint.tls() {
+            // Prepare a tls connection
+            let config = ClientConfig::builder()
+                .with_root_certificates(self.endpoint.root_store()?)
+                .with_no_client_auth();
+            let tls_connector = TlsConnector::from(Arc::new(config));
+
+            debug!("construct tls connector");
+
+            let server_name = tokio_rustls::rustls::pki_types::ServerName::try_from(server_host)
+                .map_err(|e| Error::invalid_endpoint(e.to_string()))?;
+            let tls_stream = cancellable!(
+                cancell,
+                policy::timeout(connect_timeout, "tls handshake", async {
+                    tls_connector
+                        .connect(server_name, tcp_stream)
+                        .await
+                        .map_err(Error::from_tls_io)
+                })
+            )?;
+
+            self.communicate(request, tls_stream, &pingmsg, cancell, respone, forwarded)
+                .await
+        } else {
+            self.communicate(request, tcp_stream, &pingmsg, cancell, respone, forwarded)
+                .await
+        }
+    }
+
+    fn host_header(host: &str, port: u16, tls: bool) -> String {
+        if (tls && port == 443) || (!tls && port == 80) {
+            host.to_string()
+        } else {
+            format!("{}:{}", host, port)
+        }
+    }
+
+    async fn communicate<S>(
+        &self,
+        request: http::Request<String>,
+        stream: S,
+        pingmsg: &str,
+        cancell: &CancellationToken,
+        respone: &Sender<req::Message>,
+        forwarded: &mut usize,
+    ) -> Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let (websocket, _) = cancellable!(
+            cancell,
+            policy::timeout(self.timeouts.handshake(), "websocket handshake", async {
+                handshake::client(&SpawnExecutor, request, stream)
+                    .await
+                    .map_err(Error::Handshake)
+            })
+        )?;
         let mut websocket = FragmentCollector::new(websocket);
 
         debug!("sending payload message to websocket");
@@ -115,15 +332,31 @@ impl<'a> ItdogClient<'a> {
             .write_frame(Frame::text(fastwebsockets::Payload::Borrowed(
                 pingmsg.as_bytes(),
             )))
-            .await?;
+            .await
+            .map_err(Error::WebSocket)?;
 
         debug!("waiting for server reply..");
-        return_if_cancell!(self);
 
         let mut count = 0;
+        let mut cancelled = false;
+
+        loop {
+            let first_message = if count == 0 {
+                self.timeouts.first_message()
+            } else {
+                None
+            };
+            let message = tokio::select! {
+                message = policy::timeout(first_message, "first message", async {
+                    Ok(websocket.read_frame().await)
+                }) => message?,
+                _ = cancell.cancelled() => {
+                    cancelled = true;
+                    break;
+                }
+            };
+            let message = message.map_err(Error::WebSocket)?;
 
-        // The WebSocket is also a `TryStream` over `Message`s.
-        while let Ok(message) = websocket.read_frame().await {
             match message.opcode {
                 fastwebsockets::OpCode::Text => {
                     let text = String::from_utf8(message.payload.to_vec())?;
@@ -134,34 +367,44 @@ impl<'a> ItdogClient<'a> {
                     if text.contains("\"type\":\"finished\"") {
                         break;
                     } else {
-                        self.respone.send(serde_json::from_str(&text)?).await?;
+                        let message = serde_json::from_str(&text)
+                            .map_err(|source| Error::MalformedMessage { text, source })?;
+
+                        let sent = tokio::select! {
+                            sent = respone.send(message) => sent,
+                            _ = cancell.cancelled() => {
+                                cancelled = true;
+                                break;
+                            }
+                        };
+
+                        sent.map_err(|_| Error::ReceiverClosed)?;
+                        *forwarded += 1;
                     }
                 }
                 fastwebsockets::OpCode::Close => {
                     break;
                 }
-                fastwebsockets::OpCode::Continuation => {
-                    println!("........?");
-                }
-                fastwebsockets::OpCode::Binary => {
-                    println!("...........?");
-                }
-                _ => {
-                    println!("..................................");
+                opcode => {
+                    trace!("ignore {:?} frame", opcode);
                 }
             }
-            return_if_cancell!(self);
         }
 
         debug!("sending close message to websocket");
 
         websocket
-            .write_frame(Frame::close_raw(vec![].into()))
-            .await?;
+            .write_frame(Frame::close(1000, b""))
+            .await
+            .map_err(Error::WebSocket)?;
 
-        debug!("done! received text message count = {count}");
+        debug!("done! received text message count = {count}, cancelled = {cancelled}");
 
-        Ok(())
+        if cancelled {
+            Err(Error::Cancelled)
+        } else {
+            Ok(())
+        }
     }
 }
 
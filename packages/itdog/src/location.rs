@@ -0,0 +1,129 @@
+/// The provinces of China, including the special administrative regions.
+const PROVINCES: [&str; 34] = [
+    "北京",
+    "天津",
+    "上海",
+    "重庆",
+    "河北",
+    "山西",
+    "辽宁",
+    "吉林",
+    "黑龙江",
+    "江苏",
+    "浙江",
+    "安徽",
+    "福建",
+    "江西",
+    "山东",
+    "河南",
+    "湖北",
+    "湖南",
+    "广东",
+    "海南",
+    "四川",
+    "贵州",
+    "云南",
+    "陕西",
+    "甘肃",
+    "青海",
+    "台湾",
+    "内蒙古",
+    "广西",
+    "西藏",
+    "宁夏",
+    "新疆",
+    "香港",
+    "澳门",
+];
+
+/// The carriers of China.
+const ISPS: [&str; 10] = [
+    "电信",
+    "联通",
+    "移动",
+    "广电",
+    "铁通",
+    "教育网",
+    "鹏博士",
+    "长城宽带",
+    "BGP",
+    "多线",
+];
+
+/// The countries of overseas nodes.
+const COUNTRIES: [&str; 24] = [
+    "美国",
+    "日本",
+    "韩国",
+    "新加坡",
+    "德国",
+    "英国",
+    "法国",
+    "俄罗斯",
+    "加拿大",
+    "澳大利亚",
+    "印度尼西亚",
+    "印度",
+    "荷兰",
+    "巴西",
+    "泰国",
+    "越南",
+    "马来西亚",
+    "菲律宾",
+    "南非",
+    "意大利",
+    "西班牙",
+    "瑞典",
+    "土耳其",
+    "阿联酋",
+];
+
+const CHINA: &str = "中国";
+
+/// The structured location of node name, such as `江苏电信`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Location {
+    province: Option<String>,
+
+    isp: Option<String>,
+
+    country: Option<String>,
+}
+
+impl Location {
+    /// Parse the node name such as `江苏电信`, `中国香港` or `美国洛杉矶`,
+    /// the part can not be recognized is `None`.
+    pub fn parse(name: &str) -> Self {
+        let name = name.trim();
+        let rest = name.strip_prefix(CHINA).unwrap_or(name);
+        let province = PROVINCES.iter().find(|v| rest.starts_with(*v));
+        let isp = ISPS.iter().find(|v| rest.contains(*v));
+        let country = if province.is_some() || rest.len() < name.len() {
+            Some(CHINA)
+        } else if let Some(country) = COUNTRIES.iter().find(|v| name.starts_with(*v)) {
+            Some(*country)
+        } else {
+            // the carriers except BGP only serve in China
+            isp.filter(|v| !matches!(**v, "BGP" | "多线"))
+                .map(|_| CHINA)
+        };
+
+        Self {
+            province: province.map(|v| v.to_string()),
+            isp: isp.map(|v| v.to_string()),
+            country: country.map(String::from),
+        }
+    }
+
+    pub fn province(&self) -> Option<&str> {
+        self.province.as_deref()
+    }
+
+    pub fn isp(&self) -> Option<&str> {
+        self.isp.as_deref()
+    }
+
+    pub fn country(&self) -> Option<&str> {
+        self.country.as_deref()
+    }
+}
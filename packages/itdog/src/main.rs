@@ -1,8 +1,26 @@
 use cote::prelude::*;
+use itdog::csv_line;
+use itdog::parse_duration;
+use itdog::parse_status_list;
+use itdog::read_targets;
+use itdog::Endpoint;
 use itdog::ItdogClient;
+use itdog::Message;
+use itdog::OutputFormat;
+use itdog::QueryOptions;
+use itdog::RetryPolicy;
+use itdog::Sample;
+use itdog::Target;
+use itdog::Thresholds;
+use itdog::Timeouts;
+use itdog::DEFAULT_HTTP_URL;
 use itdog::DEFAULT_KEY;
+use itdog::DEFAULT_WS_URL;
+use itdog::EXIT_VIOLATION;
 use prettytable::Row;
 use prettytable::Table;
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
 use tracing::level_filters::LevelFilter;
 
 #[derive(Debug, Cote)]
@@ -13,8 +31,13 @@ pub struct Httping {
     key: String,
 
     /// The target url, for example: www.baidu.com
-    #[pos(force = true)]
-    host: String,
+    #[pos()]
+    host: Option<String>,
+
+    /// Read the targets from the file or stdin if it is `-`, one per line
+    /// with optional overrides, such as `www.baidu.com method=head status=200,301`
+    #[arg(alias = "-f")]
+    file: Option<String>,
 
     /// Enable debug mode
     #[arg(alias = "-d")]
@@ -23,6 +46,100 @@ pub struct Httping {
     /// Enable verbose mode
     #[arg(alias = "-v")]
     verbose: bool,
+
+    /// Set the line of nodes: all, telecom, unicom, mobile or overseas
+    #[arg(value = "all")]
+    line: String,
+
+    /// Set the check mode: fast or slow
+    #[arg(alias = "-m", value = "fast")]
+    mode: String,
+
+    /// Set the ip address of host, empty means resolve by node
+    #[arg(value = "")]
+    ipv4: String,
+
+    /// Set the http method: get, post or head
+    #[arg(alias = "-X", value = "get")]
+    method: String,
+
+    /// Set the referer of request
+    #[arg(value = "")]
+    referer: String,
+
+    /// Set the user agent of request
+    #[arg(alias = "-u", value = "")]
+    useragent: String,
+
+    /// Set the cookies of request
+    #[arg(value = "")]
+    cookies: String,
+
+    /// Set the maximum redirect count
+    #[arg(value = 5i32)]
+    redirect: i32,
+
+    /// Set the dns server type: isp or custom
+    #[arg(value = "isp")]
+    dns_type: String,
+
+    /// Set the dns server, using with custom dns server type
+    #[arg(value = "")]
+    dns_server: String,
+
+    /// Set the http url of itdog
+    #[arg(value = DEFAULT_HTTP_URL)]
+    http_url: String,
+
+    /// Set the websocket url of itdog, tls is enabled for `wss` scheme
+    #[arg(value = DEFAULT_WS_URL)]
+    ws_url: String,
+
+    /// Trust the certificates in the PEM file
+    ca_cert: Option<String>,
+
+    /// Set the timeout of connecting itdog, such as 500ms, 10s or 0 for no limit
+    #[arg(value = "10s")]
+    connect_timeout: String,
+
+    /// Set the timeout of websocket handshake
+    #[arg(value = "10s")]
+    handshake_timeout: String,
+
+    /// Set the timeout of waiting the first message
+    #[arg(value = "30s")]
+    first_message_timeout: String,
+
+    /// Set the timeout of whole query, including the retries
+    #[arg(alias = "-t", value = "120s")]
+    timeout: String,
+
+    /// Set the maximum retry count of transient failure
+    #[arg(value = 2usize)]
+    retry: usize,
+
+    /// Set the delay before first retry, it is doubled for each retry
+    #[arg(value = "500ms")]
+    retry_backoff: String,
+
+    /// Set the output format: table, json, ndjson or csv
+    #[arg(alias = "-o", value = "table")]
+    output: String,
+
+    /// Fail if the total cost of any node exceeds the duration, such as 2s
+    #[arg(value = "0")]
+    max_total: String,
+
+    /// Fail if the ratio of successful nodes is below the value, such as 0.95
+    min_success_ratio: Option<f64>,
+
+    /// Set the expected http status such as 200,301, fail if any node returns other status
+    #[arg(value = "")]
+    expect_status: String,
+
+    /// Fail if the p95 of total cost of successful nodes exceeds the duration
+    #[arg(value = "0")]
+    max_p95: String,
 }
 
 #[tokio::main]
@@ -38,8 +155,33 @@ async fn main() -> color_eyre::Result<()> {
     let Httping {
         key,
         host,
+        file,
         debug,
         verbose,
+        line,
+        mode,
+        ipv4,
+        method,
+        referer,
+        useragent,
+        cookies,
+        redirect,
+        dns_type,
+        dns_server,
+        http_url,
+        ws_url,
+        ca_cert,
+        connect_timeout,
+        handshake_timeout,
+        first_message_timeout,
+        timeout,
+        retry,
+        retry_backoff,
+        output,
+        max_total,
+        min_success_ratio,
+        expect_status,
+        max_p95,
     } = Httping::parse_env()?;
 
     if verbose {
@@ -57,23 +199,207 @@ async fn main() -> color_eyre::Result<()> {
         .install_default()
         .unwrap();
 
-    let (_, cancell) = tokio::sync::oneshot::channel();
-    let (send, mut recv) = tokio::sync::mpsc::channel(128);
+    let cancell = CancellationToken::new();
+    let mut endpoint = Endpoint::new(http_url, ws_url);
+
+    if let Some(path) = ca_cert {
+        endpoint = endpoint.with_root_certs_pem(path)?;
+    }
+
+    let timeouts = Timeouts::default()
+        .with_connect(parse_duration(&connect_timeout)?)
+        .with_handshake(parse_duration(&handshake_timeout)?)
+        .with_first_message(parse_duration(&first_message_timeout)?)
+        .with_overall(parse_duration(&timeout)?);
+    let retry = RetryPolicy::default()
+        .with_max_retries(retry)
+        .with_initial_backoff(parse_duration(&retry_backoff)?.unwrap_or_default());
+    let itdog = ItdogClient::builder()
+        .with_key(key)
+        .with_endpoint(endpoint)
+        .with_timeouts(timeouts)
+        .with_retry(retry)
+        .build()?;
+    let options = QueryOptions::default()
+        .with_line(line.parse()?)
+        .with_mode(mode.parse()?)
+        .with_ipv4(ipv4)
+        .with_method(method.parse()?)
+        .with_referer(referer)
+        .with_useragent(useragent)
+        .with_cookies(cookies)
+        .with_redirect(redirect)
+        .with_dns_type(dns_type.parse()?)
+        .with_dns_server(dns_server);
+
+    options.validate()?;
+
+    let output: OutputFormat = output.parse()?;
+    let thresholds = Thresholds::default()
+        .with_max_total(parse_duration(&max_total)?)
+        .with_min_success_ratio(min_success_ratio)
+        .with_expect_status(parse_status_list(&expect_status)?)
+        .with_max_p95(parse_duration(&max_p95)?);
+
+    thresholds.validate()?;
+
+    let mut targets = vec![];
 
+    if let Some(host) = host {
+        targets.push(Target::new(host));
+    }
+    if let Some(path) = file {
+        targets.extend(read_targets(&path)?);
+    }
+    if targets.is_empty() {
+        return Err(color_eyre::eyre::eyre!(
+            "no target given, set the host or the targets file"
+        ));
+    }
+
+    let ctrl_c = cancell.clone();
+
+    // cancel the query and close the websocket when got ctrl-c
     tokio::spawn(async move {
-        let mut itdog = ItdogClient::new(&key, &host, cancell, send);
-        itdog.query().await.unwrap();
+        if tokio::signal::ctrl_c().await.is_ok() {
+            ctrl_c.cancel();
+        }
     });
 
-    let mut messages = vec![];
+    if output == OutputFormat::Csv {
+        let mut header = vec!["host".to_string()];
 
-    while let Some(req) = recv.recv().await {
-        messages.push(req);
+        header.extend(Message::field_names());
+        println!("{}", csv_line(header));
     }
 
+    let mut records = vec![];
+    let mut violations = vec![];
+    let mut errors = vec![];
+
+    for target in targets.iter() {
+        if cancell.is_cancelled() {
+            break;
+        }
+
+        let host = target.host();
+        let mut options = options.clone();
+        let mut thresholds = thresholds.clone();
+
+        if let Some(method) = target.method() {
+            options = options.with_method(method);
+        }
+        if !target.expect_status().is_empty() {
+            thresholds = thresholds.with_expect_status(target.expect_status().to_vec());
+        }
+
+        let (ret, messages) = query(&itdog, host, &options, cancell.clone(), output).await;
+        let messages = messages?;
+
+        if let Err(e) = ret {
+            errors.push((host, e));
+        }
+        if output == OutputFormat::Table {
+            if targets.len() > 1 {
+                println!("{} ({})", host, messages.len());
+            }
+            print_table(&messages);
+        }
+
+        let samples: Vec<_> = messages
+            .iter()
+            .map(|msg| Sample::new(msg.name(), msg.http_code(), msg.all_time().duration()))
+            .collect();
+
+        violations.extend(thresholds.check(&samples).into_iter().map(|v| (host, v)));
+        if output == OutputFormat::Json {
+            records.extend(messages.into_iter().map(|message| Record {
+                host: host.to_string(),
+                message,
+            }));
+        }
+    }
+    if output == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&records)?);
+    }
+    if !violations.is_empty() {
+        eprintln!("{} threshold violations:", violations.len());
+        for (host, violation) in violations.iter() {
+            eprintln!("  {}: {}", host, violation);
+        }
+    }
+    if targets.len() == 1 {
+        if let Some((_, e)) = errors.pop() {
+            return Err(e.into());
+        }
+    } else if !errors.is_empty() {
+        for (host, e) in errors.iter() {
+            eprintln!("{}: {}", host, e);
+        }
+        return Err(color_eyre::eyre::eyre!(
+            "{} of {} targets failed",
+            errors.len(),
+            targets.len()
+        ));
+    }
+    if !violations.is_empty() {
+        std::process::exit(EXIT_VIOLATION);
+    }
+    Ok(())
+}
+
+/// A message of host in the json, ndjson and csv output.
+#[derive(Debug, Serialize)]
+struct Record {
+    host: String,
+
+    #[serde(flatten)]
+    message: Message,
+}
+
+/// Query the host and collect the messages, the message is printed as soon as it arrived
+/// if the output format support.
+async fn query(
+    itdog: &ItdogClient,
+    host: &str,
+    options: &QueryOptions,
+    cancell: CancellationToken,
+    output: OutputFormat,
+) -> (itdog::Result<()>, serde_json::Result<Vec<Message>>) {
+    let (send, mut recv) = tokio::sync::mpsc::channel(128);
+
+    tokio::join!(itdog.query(host, options, cancell, send), async move {
+        let mut messages = vec![];
+
+        while let Some(message) = recv.recv().await {
+            match output {
+                OutputFormat::Ndjson => {
+                    let record = Record {
+                        host: host.to_string(),
+                        message,
+                    };
+
+                    println!("{}", serde_json::to_string(&record)?);
+                    messages.push(record.message);
+                }
+                OutputFormat::Csv => {
+                    let mut row = vec![host.to_string()];
+
+                    row.extend(message.construct_row());
+                    println!("{}", csv_line(row));
+                    messages.push(message);
+                }
+                _ => messages.push(message),
+            }
+        }
+        Ok::<_, serde_json::Error>(messages)
+    })
+}
+
+fn print_table(messages: &[Message]) {
     let mut table = Table::new();
 
-    table.add_row(Row::from_iter(itdog::Message::construct_header()));
+    table.add_row(Row::from_iter(Message::construct_header()));
     if !messages.is_empty() {
         messages
             .iter()
@@ -81,8 +407,7 @@ async fn main() -> color_eyre::Result<()> {
             .for_each(|v| {
                 table.add_row(Row::from_iter(v));
             });
-        table.add_row(Row::from_iter(itdog::Message::construct_header()));
+        table.add_row(Row::from_iter(Message::construct_header()));
         table.printstd();
     }
-    Ok(())
 }
@@ -0,0 +1,262 @@
+//! A local server emulating the task page and websocket of itdog, used by offline tests.
+//!
+//! ```no_run
+//! # async fn run() -> color_eyre::Result<()> {
+//! use itdog::mock::MockServer;
+//!
+//! let server = MockServer::builder()
+//!     .with_message(MockServer::node("江苏电信", "1.1.1.1", 200, "0.123"))
+//!     .start()
+//!     .await?;
+//! let client = itdog::ItdogClient::builder()
+//!     .with_endpoint(server.endpoint())
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use fastwebsockets::upgrade;
+use fastwebsockets::FragmentCollector;
+use fastwebsockets::Frame;
+use fastwebsockets::OpCode;
+use fastwebsockets::Payload;
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::Method;
+use hyper::Request;
+use hyper::Response;
+use hyper::StatusCode;
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use crate::Endpoint;
+use crate::ReqClient;
+use crate::DEFAULT_KEY;
+
+pub const MOCK_TASK_ID: &str = "mock_task_id";
+
+#[derive(Debug, Clone)]
+struct MockConfig {
+    key: String,
+
+    task_id: Option<String>,
+
+    messages: Vec<serde_json::Value>,
+
+    finished: bool,
+
+    disconnect: bool,
+}
+
+#[derive(Debug)]
+pub struct MockServerBuilder {
+    config: MockConfig,
+}
+
+impl Default for MockServerBuilder {
+    fn default() -> Self {
+        Self {
+            config: MockConfig {
+                key: DEFAULT_KEY.to_string(),
+                task_id: Some(MOCK_TASK_ID.to_string()),
+                messages: vec![],
+                finished: true,
+                disconnect: false,
+            },
+        }
+    }
+}
+
+impl MockServerBuilder {
+    /// Set the key used to verify the task token.
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.config.key = key.into();
+        self
+    }
+
+    /// Set the task id in the page, `None` means the page has no task id.
+    pub fn with_task_id(mut self, task_id: Option<String>) -> Self {
+        self.config.task_id = task_id;
+        self
+    }
+
+    /// Add a message sent to client over websocket.
+    pub fn with_message(mut self, message: serde_json::Value) -> Self {
+        self.config.messages.push(message);
+        self
+    }
+
+    pub fn with_messages(mut self, messages: Vec<serde_json::Value>) -> Self {
+        self.config.messages.extend(messages);
+        self
+    }
+
+    /// Drop the connection after all messages without the finished message and close frame.
+    pub fn with_disconnect(mut self, disconnect: bool) -> Self {
+        self.config.disconnect = disconnect;
+        self
+    }
+
+    /// Send the finished message after all messages, or keep the websocket open until client closed it.
+    pub fn with_finished(mut self, finished: bool) -> Self {
+        self.config.finished = finished;
+        self
+    }
+
+    /// Listen on a random local port and serve the connections in background.
+    pub async fn start(self) -> color_eyre::Result<MockServer> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let config = Arc::new(self.config);
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let config = config.clone();
+
+                tokio::spawn(async move {
+                    let io = TokioIo::new(stream);
+                    let service = service_fn(move |req| serve(config.clone(), req));
+
+                    if let Err(e) = http1::Builder::new()
+                        .serve_connection(io, service)
+                        .with_upgrades()
+                        .await
+                    {
+                        debug!("mock connection closed with error: {e:?}");
+                    }
+                });
+            }
+        });
+
+        debug!("mock server listening on `{addr}`");
+        Ok(MockServer { addr, handle })
+    }
+}
+
+/// The mock server, it stopped when dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+
+    handle: JoinHandle<()>,
+}
+
+impl MockServer {
+    pub fn builder() -> MockServerBuilder {
+        MockServerBuilder::default()
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Return the endpoint pointed to the mock server.
+    pub fn endpoint(&self) -> Endpoint {
+        Endpoint::new(
+            format!("http://{}/http/", self.addr),
+            format!("ws://{}/websockets", self.addr),
+        )
+    }
+
+    /// Construct a message of node same as itdog.
+    pub fn node(name: &str, ip: &str, http_code: i32, all_time: &str) -> serde_json::Value {
+        serde_json::json!({
+            "ip": ip,
+            "http_code": http_code,
+            "all_time": all_time,
+            "dns_time": "0.010",
+            "connect_time": "0.020",
+            "download_time": "0.030",
+            "redirect": 0,
+            "redirect_time": "0.000",
+            "name": name,
+        })
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn serve(
+    config: Arc<MockConfig>,
+    mut req: Request<Incoming>,
+) -> color_eyre::Result<Response<Full<Bytes>>> {
+    debug!("mock server got request `{} {}`", req.method(), req.uri());
+    match (req.method(), req.uri().path()) {
+        (&Method::POST, "/http/") => {
+            let html = match &config.task_id {
+                Some(task_id) => {
+                    format!("<html><script>var task_id='{}';</script></html>", task_id)
+                }
+                None => "<html></html>".to_string(),
+            };
+
+            Ok(Response::new(Full::new(Bytes::from(html))))
+        }
+        (&Method::GET, "/websockets") if upgrade::is_upgrade_request(&req) => {
+            let (respone, fut) = upgrade::upgrade(&mut req)?;
+
+            tokio::spawn(async move {
+                if let Err(e) = reply(config, fut).await {
+                    debug!("mock websocket closed with error: {e:?}");
+                }
+            });
+            Ok(respone.map(|_| Full::default()))
+        }
+        _ => Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Full::default())?),
+    }
+}
+
+async fn reply(config: Arc<MockConfig>, fut: upgrade::UpgradeFut) -> color_eyre::Result<()> {
+    let mut websocket = FragmentCollector::new(fut.await?);
+    let frame = websocket.read_frame().await?;
+
+    if frame.opcode != OpCode::Text {
+        return Err(color_eyre::eyre::eyre!("expect a text frame"));
+    }
+
+    let payload: serde_json::Value = serde_json::from_slice(&frame.payload)?;
+    let task_id = config.task_id.as_deref().unwrap_or_default();
+    let md5 = ReqClient::generate_md5(task_id, &config.key);
+
+    if payload["task_id"] != task_id || payload["task_token"] != md5[8..24] {
+        websocket
+            .write_frame(Frame::close(1008, b"invalid task token"))
+            .await?;
+        return Ok(());
+    }
+    for message in config.messages.iter() {
+        let text = message.to_string();
+
+        websocket
+            .write_frame(Frame::text(Payload::Owned(text.into_bytes())))
+            .await?;
+    }
+    if config.disconnect {
+        return Ok(());
+    }
+    if config.finished {
+        websocket
+            .write_frame(Frame::text(Payload::Borrowed(b"{\"type\":\"finished\"}")))
+            .await?;
+    }
+
+    // wait the close frame from client
+    while let Ok(frame) = websocket.read_frame().await {
+        if frame.opcode == OpCode::Close {
+            break;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,178 @@
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+
+use crate::param::CheckMode;
+use crate::param::DnsServerType;
+use crate::param::HttpMethod;
+use crate::param::Line;
+use crate::Error;
+
+/// The options of itdog http request, default is same as the page of itdog.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct QueryOptions {
+    line: Line,
+
+    mode: CheckMode,
+
+    ipv4: String,
+
+    method: HttpMethod,
+
+    referer: String,
+
+    useragent: String,
+
+    cookies: String,
+
+    redirect: i32,
+
+    dns_type: DnsServerType,
+
+    dns_server: String,
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        Self {
+            line: Default::default(),
+            mode: Default::default(),
+            ipv4: Default::default(),
+            method: Default::default(),
+            referer: Default::default(),
+            useragent: Default::default(),
+            cookies: Default::default(),
+            redirect: 5,
+            dns_type: Default::default(),
+            dns_server: Default::default(),
+        }
+    }
+}
+
+impl QueryOptions {
+    // Get api, automate generated by api-gen ...
+    pub fn line(&self) -> Line {
+        self.line
+    }
+
+    pub fn mode(&self) -> CheckMode {
+        self.mode
+    }
+
+    pub fn ipv4(&self) -> &str {
+        self.ipv4.as_ref()
+    }
+
+    pub fn method(&self) -> HttpMethod {
+        self.method
+    }
+
+    pub fn referer(&self) -> &str {
+        self.referer.as_ref()
+    }
+
+    pub fn useragent(&self) -> &str {
+        self.useragent.as_ref()
+    }
+
+    pub fn cookies(&self) -> &str {
+        self.cookies.as_ref()
+    }
+
+    pub fn redirect(&self) -> i32 {
+        self.redirect
+    }
+
+    pub fn dns_type(&self) -> DnsServerType {
+        self.dns_type
+    }
+
+    pub fn dns_server(&self) -> &str {
+        self.dns_server.as_ref()
+    }
+
+    // With api, automate generated by api-gen ...
+    pub fn with_line(mut self, value: Line) -> Self {
+        self.line = value;
+        self
+    }
+
+    pub fn with_mode(mut self, value: CheckMode) -> Self {
+        self.mode = value;
+        self
+    }
+
+    pub fn with_ipv4(mut self, value: String) -> Self {
+        self.ipv4 = value;
+        self
+    }
+
+    pub fn with_method(mut self, value: HttpMethod) -> Self {
+        self.method = value;
+        self
+    }
+
+    pub fn with_referer(mut self, value: String) -> Self {
+        self.referer = value;
+        self
+    }
+
+    pub fn with_useragent(mut self, value: String) -> Self {
+        self.useragent = value;
+        self
+    }
+
+    pub fn with_cookies(mut self, value: String) -> Self {
+        self.cookies = value;
+        self
+    }
+
+    pub fn with_redirect(mut self, value: i32) -> Self {
+        self.redirect = value;
+        self
+    }
+
+    pub fn with_dns_type(mut self, value: DnsServerType) -> Self {
+        self.dns_type = value;
+        self
+    }
+
+    pub fn with_dns_server(mut self, value: String) -> Self {
+        self.dns_server = value;
+        self
+    }
+
+    /// Check the options, reject invalid value or combination before sending request.
+    pub fn validate(&self) -> crate::Result<()> {
+        validate_params(&self.ipv4, self.redirect, self.dns_type, &self.dns_server)
+    }
+}
+
+pub(crate) fn validate_params(
+    ipv4: &str,
+    redirect: i32,
+    dns_type: DnsServerType,
+    dns_server: &str,
+) -> crate::Result<()> {
+    if !ipv4.is_empty() && ipv4.parse::<Ipv4Addr>().is_err() {
+        return Err(Error::invalid_param(format!(
+            "invalid ipv4 address `{}`",
+            ipv4
+        )));
+    }
+    if redirect < 0 {
+        return Err(Error::invalid_param(format!(
+            "invalid redirect count `{}`",
+            redirect
+        )));
+    }
+    match dns_type {
+        DnsServerType::Isp if !dns_server.is_empty() => Err(Error::invalid_param(format!(
+            "dns server `{}` requires custom dns server type",
+            dns_server
+        ))),
+        DnsServerType::Custom if dns_server.parse::<IpAddr>().is_err() => Err(
+            Error::invalid_param(format!("invalid custom dns server `{}`", dns_server)),
+        ),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,188 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::Error;
+
+/// The check mode of itdog, `fast` return quickly but `slow` check more nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckMode {
+    #[default]
+    Fast,
+
+    Slow,
+}
+
+impl CheckMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckMode::Fast => "fast",
+            CheckMode::Slow => "slow",
+        }
+    }
+}
+
+impl FromStr for CheckMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fast" => Ok(Self::Fast),
+            "slow" => Ok(Self::Slow),
+            _ => Err(Error::invalid_param(format!("invalid check mode `{}`", s))),
+        }
+    }
+}
+
+impl Display for CheckMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The http method used by itdog nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HttpMethod {
+    #[default]
+    Get,
+
+    Post,
+
+    Head,
+}
+
+impl HttpMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpMethod::Get => "get",
+            HttpMethod::Post => "post",
+            HttpMethod::Head => "head",
+        }
+    }
+}
+
+impl FromStr for HttpMethod {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "get" => Ok(Self::Get),
+            "post" => Ok(Self::Post),
+            "head" => Ok(Self::Head),
+            _ => Err(Error::invalid_param(format!("invalid http method `{}`", s))),
+        }
+    }
+}
+
+impl Display for HttpMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl From<HttpMethod> for http::Method {
+    fn from(value: HttpMethod) -> Self {
+        match value {
+            HttpMethod::Get => http::Method::GET,
+            HttpMethod::Post => http::Method::POST,
+            HttpMethod::Head => http::Method::HEAD,
+        }
+    }
+}
+
+/// Which dns server the nodes used to resolve the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DnsServerType {
+    /// Use the dns server of node's ISP.
+    #[default]
+    Isp,
+
+    /// Use the dns server set by `dns_server`.
+    Custom,
+}
+
+impl DnsServerType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DnsServerType::Isp => "isp",
+            DnsServerType::Custom => "custom",
+        }
+    }
+}
+
+impl FromStr for DnsServerType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "isp" => Ok(Self::Isp),
+            "custom" => Ok(Self::Custom),
+            _ => Err(Error::invalid_param(format!(
+                "invalid dns server type `{}`",
+                s
+            ))),
+        }
+    }
+}
+
+impl Display for DnsServerType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The line of nodes, serialized to the value used by itdog page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+pub enum Line {
+    #[default]
+    #[serde(rename = "")]
+    All,
+
+    #[serde(rename = "1")]
+    Telecom,
+
+    #[serde(rename = "2")]
+    Unicom,
+
+    #[serde(rename = "3")]
+    Mobile,
+
+    #[serde(rename = "5")]
+    Overseas,
+}
+
+impl Line {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Line::All => "all",
+            Line::Telecom => "telecom",
+            Line::Unicom => "unicom",
+            Line::Mobile => "mobile",
+            Line::Overseas => "overseas",
+        }
+    }
+}
+
+impl FromStr for Line {
+    type Err = Error;
+
+    /// Parse the line from english name, chinese name or the value of itdog page.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "" | "all" | "全部" => Ok(Self::All),
+            "1" | "telecom" | "电信" => Ok(Self::Telecom),
+            "2" | "unicom" | "联通" => Ok(Self::Unicom),
+            "3" | "mobile" | "移动" => Ok(Self::Mobile),
+            "5" | "overseas" | "海外" => Ok(Self::Overseas),
+            _ => Err(Error::invalid_param(format!("invalid line `{}`", s))),
+        }
+    }
+}
+
+impl Display for Line {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
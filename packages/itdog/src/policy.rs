@@ -0,0 +1,205 @@
+use std::future::Future;
+use std::time::Duration;
+
+use crate::Error;
+
+/// The timeouts of each stage of query, `None` means no limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    connect: Option<Duration>,
+
+    handshake: Option<Duration>,
+
+    first_message: Option<Duration>,
+
+    overall: Option<Duration>,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            connect: Some(Duration::from_secs(10)),
+            handshake: Some(Duration::from_secs(10)),
+            first_message: Some(Duration::from_secs(30)),
+            overall: Some(Duration::from_secs(120)),
+        }
+    }
+}
+
+impl Timeouts {
+    /// No timeout for all the stages.
+    pub fn unlimited() -> Self {
+        Self {
+            connect: None,
+            handshake: None,
+            first_message: None,
+            overall: None,
+        }
+    }
+
+    /// Timeout of requesting the task page and connecting to the websocket server.
+    pub fn with_connect(mut self, connect: Option<Duration>) -> Self {
+        self.connect = connect;
+        self
+    }
+
+    /// Timeout of the websocket handshake.
+    pub fn with_handshake(mut self, handshake: Option<Duration>) -> Self {
+        self.handshake = handshake;
+        self
+    }
+
+    /// Timeout of waiting the first message after the task is sent.
+    pub fn with_first_message(mut self, first_message: Option<Duration>) -> Self {
+        self.first_message = first_message;
+        self
+    }
+
+    /// Timeout of the whole query, including the retries.
+    pub fn with_overall(mut self, overall: Option<Duration>) -> Self {
+        self.overall = overall;
+        self
+    }
+
+    pub fn connect(&self) -> Option<Duration> {
+        self.connect
+    }
+
+    pub fn handshake(&self) -> Option<Duration> {
+        self.handshake
+    }
+
+    pub fn first_message(&self) -> Option<Duration> {
+        self.first_message
+    }
+
+    pub fn overall(&self) -> Option<Duration> {
+        self.overall
+    }
+}
+
+/// Retry the query failed with transient error, the delay is increased exponentially.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: usize,
+
+    initial_backoff: Duration,
+
+    max_backoff: Duration,
+
+    multiplier: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry the query.
+    pub fn never() -> Self {
+        Self::default().with_max_retries(0)
+    }
+
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: u32) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    pub fn initial_backoff(&self) -> Duration {
+        self.initial_backoff
+    }
+
+    pub fn max_backoff(&self) -> Duration {
+        self.max_backoff
+    }
+
+    pub fn multiplier(&self) -> u32 {
+        self.multiplier
+    }
+
+    /// The delay before the `retry`th retry, `retry` starts from 0.
+    pub fn backoff(&self, retry: usize) -> Duration {
+        let factor = self
+            .multiplier
+            .checked_pow(retry.try_into().unwrap_or(u32::MAX))
+            .unwrap_or(u32::MAX);
+
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |v| v.min(self.max_backoff))
+    }
+}
+
+/// Parse the duration such as `500ms`, `10s`, `2m` or `1h`, the unit default to seconds.
+///
+/// The `0`, `none` and `off` are parsed as `None`, which means no limit.
+pub fn parse_duration(s: &str) -> crate::Result<Option<Duration>> {
+    let s = s.trim().to_lowercase();
+
+    if matches!(s.as_str(), "none" | "off") {
+        return Ok(None);
+    }
+
+    let pos = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (value, unit) = s.split_at(pos);
+    let value = value
+        .parse::<f64>()
+        .map_err(|_| Error::invalid_param(format!("invalid duration `{}`", s)))?;
+    let secs = match unit.trim() {
+        "ms" => value / 1000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        _ => return Err(Error::invalid_param(format!("invalid duration `{}`", s))),
+    };
+
+    if secs == 0.0 {
+        Ok(None)
+    } else {
+        Duration::try_from_secs_f64(secs)
+            .map(Some)
+            .map_err(|_| Error::invalid_param(format!("invalid duration `{}`", s)))
+    }
+}
+
+/// Wait the future, return [`Error::Timeout`] with `stage` if it is not finished in time.
+pub(crate) async fn timeout<T>(
+    duration: Option<Duration>,
+    stage: &'static str,
+    fut: impl Future<Output = crate::Result<T>>,
+) -> crate::Result<T> {
+    match duration {
+        Some(duration) => tokio::time::timeout(duration, fut)
+            .await
+            .map_err(|_| Error::Timeout(stage))?,
+        None => fut.await,
+    }
+}
@@ -1,19 +1,57 @@
+use std::time::Duration;
+
+use crate::options::validate_params;
+use crate::options::QueryOptions;
+use crate::param::CheckMode;
+use crate::param::DnsServerType;
+use crate::param::HttpMethod;
+use crate::param::Line;
+use crate::timing::Timing;
+use crate::Error;
+
+#[derive(Debug, serde::Serialize)]
+struct Form<'a> {
+    line: Line,
+
+    host: &'a str,
+
+    host_s: &'a str,
+
+    check_mode: CheckMode,
+
+    ipv4: &'a str,
+
+    method: HttpMethod,
+
+    referer: &'a str,
+
+    ua: &'a str,
+
+    cookies: &'a str,
+
+    redirect_num: i32,
+
+    dns_server_type: DnsServerType,
+
+    dns_server: &'a str,
+}
+
 pub struct ReqClient {
     inner: reqwest::Client,
 
     header: Vec<(String, String)>,
 
-    line: String,
+    line: Line,
 
     host: String,
 
     hosts: String,
 
-    mode: String,
+    mode: CheckMode,
 
     ipv4: String,
 
-    method: String,
+    method: HttpMethod,
 
     referer: String,
 
@@ -23,7 +61,7 @@ pub struct ReqClient {
 
     redirect: i32,
 
-    dns_type: String,
+    dns_type: DnsServerType,
 
     dns_server: String,
 
@@ -34,6 +72,8 @@ pub struct ReqClient {
     end: usize,
 
     debug: bool,
+
+    timeout: Option<Duration>,
 }
 
 impl ReqClient {
@@ -49,19 +89,20 @@ impl ReqClient {
             line: Default::default(),
             host: host.clone(),
             hosts: host,
-            mode: "fast".to_string(),
+            mode: Default::default(),
             ipv4: Default::default(),
-            method: "get".to_string(),
+            method: Default::default(),
             referer: Default::default(),
             useragent: Default::default(),
             cookies: Default::default(),
             redirect: 5,
-            dns_type: "isp".to_string(),
+            dns_type: Default::default(),
             dns_server: Default::default(),
             key: key.into(),
             beg: 8,
             end: 24,
             debug: false,
+            timeout: None,
         }
     }
 
@@ -110,7 +151,7 @@ impl ReqClient {
     }
 
     // Set api, automate generated by api-gen ...
-    pub fn line(&mut self, value: String) -> &mut Self {
+    pub fn line(&mut self, value: Line) -> &mut Self {
         self.line = value;
         self
     }
@@ -121,7 +162,7 @@ impl ReqClient {
         self
     }
 
-    pub fn mode(&mut self, value: String) -> &mut Self {
+    pub fn mode(&mut self, value: CheckMode) -> &mut Self {
         self.mode = value;
         self
     }
@@ -131,7 +172,7 @@ impl ReqClient {
         self
     }
 
-    pub fn method(&mut self, value: String) -> &mut Self {
+    pub fn method(&mut self, value: HttpMethod) -> &mut Self {
         self.method = value;
         self
     }
@@ -156,7 +197,7 @@ impl ReqClient {
         self
     }
 
-    pub fn dns_type(&mut self, value: String) -> &mut Self {
+    pub fn dns_type(&mut self, value: DnsServerType) -> &mut Self {
         self.dns_type = value;
         self
     }
@@ -171,8 +212,14 @@ impl ReqClient {
         self
     }
 
+    /// Set the timeout of requesting task page, `None` means no limit.
+    pub fn timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+
     // With api, automate generated by api-gen ...
-    pub fn with_line(mut self, value: String) -> Self {
+    pub fn with_line(mut self, value: Line) -> Self {
         self.line = value;
         self
     }
@@ -183,7 +230,7 @@ impl ReqClient {
         self
     }
 
-    pub fn with_mode(mut self, value: String) -> Self {
+    pub fn with_mode(mut self, value: CheckMode) -> Self {
         self.mode = value;
         self
     }
@@ -193,7 +240,7 @@ impl ReqClient {
         self
     }
 
-    pub fn with_method(mut self, value: String) -> Self {
+    pub fn with_method(mut self, value: HttpMethod) -> Self {
         self.method = value;
         self
     }
@@ -218,7 +265,7 @@ impl ReqClient {
         self
     }
 
-    pub fn with_dns_type(mut self, value: String) -> Self {
+    pub fn with_dns_type(mut self, value: DnsServerType) -> Self {
         self.dns_type = value;
         self
     }
@@ -233,58 +280,76 @@ impl ReqClient {
         self
     }
 
-    pub async fn req_wssocket_msg(&self, url: &str) -> color_eyre::Result<String> {
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set all the request parameters from the options.
+    pub fn options(&mut self, options: &QueryOptions) -> &mut Self {
+        self.line = options.line();
+        self.mode = options.mode();
+        self.ipv4 = options.ipv4().to_string();
+        self.method = options.method();
+        self.referer = options.referer().to_string();
+        self.useragent = options.useragent().to_string();
+        self.cookies = options.cookies().to_string();
+        self.redirect = options.redirect();
+        self.dns_type = options.dns_type();
+        self.dns_server = options.dns_server().to_string();
+        self
+    }
+
+    pub fn with_options(mut self, options: &QueryOptions) -> Self {
+        self.options(options);
+        self
+    }
+
+    pub async fn req_wssocket_msg(&self, url: &str) -> crate::Result<String> {
+        validate_params(&self.ipv4, self.redirect, self.dns_type, &self.dns_server)?;
+
         let mut req_builder = self.inner.post(url);
 
         for (key, value) in self.header.iter() {
             req_builder = req_builder.header(key, value);
         }
-        let body = std::iter::zip(
-            [
-                "line",
-                "host",
-                "host_s",
-                "check_mode",
-                "ipv4",
-                "method",
-                "referer",
-                "ua",
-                "cookies",
-                "redirect_num",
-                "dns_server_type",
-                "dns_server",
-            ],
-            [
-                &self.line,
-                &self.host,
-                &self.hosts,
-                &self.mode,
-                &self.ipv4,
-                &self.method,
-                &self.referer,
-                &self.useragent,
-                &self.cookies,
-                &self.redirect.to_string(),
-                &self.dns_type,
-                &self.dns_server,
-            ],
-        )
-        .map(|(key, value)| format!("{}={}", key, value))
-        .collect::<Vec<_>>()
-        .join("&");
+        let body = serde_urlencoded::to_string(Form {
+            line: self.line,
+            host: &self.host,
+            host_s: &self.hosts,
+            check_mode: self.mode,
+            ipv4: &self.ipv4,
+            method: self.method,
+            referer: &self.referer,
+            ua: &self.useragent,
+            cookies: &self.cookies,
+            redirect_num: self.redirect,
+            dns_server_type: self.dns_type,
+            dns_server: &self.dns_server,
+        })
+        .map_err(|e| Error::invalid_param(e.to_string()))?;
 
         if self.debug {
             eprintln!("Body: {body}");
         }
 
         req_builder = req_builder.body(body);
+        if let Some(timeout) = self.timeout {
+            req_builder = req_builder.timeout(timeout);
+        }
         // sending http request
-        let resp = req_builder.send().await?;
-        let html = resp.text().await?;
+        let html = async { req_builder.send().await?.text().await }
+            .await
+            .map_err(|e| {
+                if e.is_timeout() {
+                    Error::Timeout("task page request")
+                } else {
+                    Error::Http(e)
+                }
+            })?;
 
         // find task id in result
-        let task_id = Self::find_task_id(&html, "task_id=")
-            .ok_or_else(|| color_eyre::eyre::eyre!("Can not find task_id in result page"))?;
+        let task_id = Self::find_task_id(&html, "task_id=").ok_or(Error::TaskIdNotFound)?;
 
         if self.debug {
             eprintln!("Got task id = {}", task_id);
@@ -299,7 +364,7 @@ impl ReqClient {
 
         let token = md5
             .get(self.beg..self.end)
-            .ok_or_else(|| color_eyre::eyre::eyre!("Out of range, md5 string = `{}`", md5))?;
+            .ok_or_else(|| Error::Md5Range(md5.clone()))?;
 
         Ok(format!(
             "{{\"task_id\":\"{}\",\"task_token\":\"{}\"}}",
@@ -326,23 +391,28 @@ impl ReqClient {
     }
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Message {
     ip: String,
 
     http_code: i32,
 
-    all_time: String,
+    #[serde(default)]
+    all_time: Timing,
 
-    dns_time: String,
+    #[serde(default)]
+    dns_time: Timing,
 
-    connect_time: String,
+    #[serde(default)]
+    connect_time: Timing,
 
-    download_time: String,
+    #[serde(default)]
+    download_time: Timing,
 
     redirect: i32,
 
-    redirect_time: String,
+    #[serde(default)]
+    redirect_time: Timing,
 
     name: String,
 }
@@ -357,28 +427,28 @@ impl Message {
         self.http_code
     }
 
-    pub fn all_time(&self) -> &str {
-        self.all_time.as_ref()
+    pub fn all_time(&self) -> &Timing {
+        &self.all_time
     }
 
-    pub fn dns_time(&self) -> &str {
-        self.dns_time.as_ref()
+    pub fn dns_time(&self) -> &Timing {
+        &self.dns_time
     }
 
-    pub fn connect_time(&self) -> &str {
-        self.connect_time.as_ref()
+    pub fn connect_time(&self) -> &Timing {
+        &self.connect_time
     }
 
-    pub fn download_time(&self) -> &str {
-        self.download_time.as_ref()
+    pub fn download_time(&self) -> &Timing {
+        &self.download_time
     }
 
     pub fn redirect(&self) -> i32 {
         self.redirect
     }
 
-    pub fn redirect_time(&self) -> &str {
-        self.redirect_time.as_ref()
+    pub fn redirect_time(&self) -> &Timing {
+        &self.redirect_time
     }
 
     pub fn name(&self) -> &str {
@@ -387,15 +457,32 @@ impl Message {
 
     pub fn construct_row(&self) -> Vec<String> {
         [
-            &self.name,
-            &self.ip,
+            self.name.as_str(),
+            self.ip.as_str(),
             &self.http_code.to_string(),
-            &self.all_time,
-            &self.dns_time,
-            &self.connect_time,
-            &self.download_time,
+            self.all_time.text(),
+            self.dns_time.text(),
+            self.connect_time.text(),
+            self.download_time.text(),
             &self.redirect.to_string(),
-            &self.redirect_time,
+            self.redirect_time.text(),
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    /// The field names of message, in the same order as [`construct_row`](Message::construct_row).
+    pub fn field_names() -> Vec<String> {
+        [
+            "name",
+            "ip",
+            "http_code",
+            "all_time",
+            "dns_time",
+            "connect_time",
+            "download_time",
+            "redirect",
+            "redirect_time",
         ]
         .map(String::from)
         .to_vec()
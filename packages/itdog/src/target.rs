@@ -0,0 +1,121 @@
+use std::io::Read;
+use std::str::FromStr;
+
+use crate::threshold::parse_status_list;
+use crate::Error;
+use crate::HttpMethod;
+
+/// A target with optional overrides, such as `www.baidu.com method=head status=200,301`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Target {
+    host: String,
+
+    method: Option<HttpMethod>,
+
+    expect_status: Vec<i32>,
+
+    server: Option<String>,
+}
+
+impl Target {
+    pub fn new(host: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_method(mut self, method: Option<HttpMethod>) -> Self {
+        self.method = method;
+        self
+    }
+
+    pub fn with_expect_status(mut self, expect_status: Vec<i32>) -> Self {
+        self.expect_status = expect_status;
+        self
+    }
+
+    pub fn with_server(mut self, server: Option<String>) -> Self {
+        self.server = server;
+        self
+    }
+
+    pub fn host(&self) -> &str {
+        self.host.as_str()
+    }
+
+    pub fn method(&self) -> Option<HttpMethod> {
+        self.method
+    }
+
+    pub fn expect_status(&self) -> &[i32] {
+        self.expect_status.as_slice()
+    }
+
+    pub fn server(&self) -> Option<&str> {
+        self.server.as_deref()
+    }
+}
+
+impl FromStr for Target {
+    type Err = Error;
+
+    /// Parse the host followed by the overrides `method=`, `status=` and `server=`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let host = parts
+            .next()
+            .ok_or_else(|| Error::invalid_param("empty target"))?;
+        let mut target = Target::new(host);
+
+        for part in parts {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| Error::invalid_param(format!("invalid override `{}`", part)))?;
+
+            match key {
+                "method" => target.method = Some(value.parse()?),
+                "status" => target.expect_status = parse_status_list(value)?,
+                "server" => target.server = Some(value.to_string()),
+                _ => {
+                    return Err(Error::invalid_param(format!(
+                        "unknown override `{}` of `{}`",
+                        key, host
+                    )))
+                }
+            }
+        }
+        Ok(target)
+    }
+}
+
+/// Parse the targets one per line, the empty line and line starts with `#` are ignored.
+pub fn parse_targets(text: &str) -> crate::Result<Vec<Target>> {
+    text.lines()
+        .enumerate()
+        .map(|(idx, line)| (idx, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(idx, line)| {
+            line.parse::<Target>().map_err(|e| match e {
+                Error::InvalidParam(msg) => {
+                    Error::invalid_param(format!("{} at line {}", msg, idx + 1))
+                }
+                e => e,
+            })
+        })
+        .collect()
+}
+
+/// Read the targets from the file, or stdin if the path is `-`.
+pub fn read_targets(path: &str) -> crate::Result<Vec<Target>> {
+    let text = if path == "-" {
+        let mut text = String::new();
+
+        std::io::stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        std::fs::read_to_string(path)?
+    };
+
+    parse_targets(&text)
+}
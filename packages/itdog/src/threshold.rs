@@ -0,0 +1,256 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use crate::Error;
+
+/// Exit code of the command line tools when the thresholds are violated.
+pub const EXIT_VIOLATION: i32 = 2;
+
+/// The result of one node evaluated by [`Thresholds`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    name: String,
+
+    status: i32,
+
+    total: Option<Duration>,
+}
+
+impl Sample {
+    pub fn new(name: impl Into<String>, status: i32, total: Option<Duration>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            total,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn status(&self) -> i32 {
+        self.status
+    }
+
+    pub fn total(&self) -> Option<Duration> {
+        self.total
+    }
+}
+
+/// A threshold not satisfied by the samples.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    NoSample,
+
+    Status {
+        name: String,
+
+        status: i32,
+    },
+
+    Total {
+        name: String,
+
+        total: Duration,
+
+        max: Duration,
+    },
+
+    SuccessRatio {
+        ratio: f64,
+
+        min: f64,
+    },
+
+    P95 {
+        p95: Duration,
+
+        max: Duration,
+    },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::NoSample => write!(f, "no result received"),
+            Violation::Status { name, status } => {
+                write!(f, "{}: unexpected status {}", name, status)
+            }
+            Violation::Total { name, total, max } => write!(
+                f,
+                "{}: total cost {:.3}s exceeds {:.3}s",
+                name,
+                total.as_secs_f64(),
+                max.as_secs_f64()
+            ),
+            Violation::SuccessRatio { ratio, min } => {
+                write!(f, "success ratio {:.3} is below {:.3}", ratio, min)
+            }
+            Violation::P95 { p95, max } => write!(
+                f,
+                "p95 of total cost {:.3}s exceeds {:.3}s",
+                p95.as_secs_f64(),
+                max.as_secs_f64()
+            ),
+        }
+    }
+}
+
+/// The thresholds evaluated over the results of all nodes, `None` means no limit.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Thresholds {
+    max_total: Option<Duration>,
+
+    min_success_ratio: Option<f64>,
+
+    expect_status: Vec<i32>,
+
+    max_p95: Option<Duration>,
+}
+
+impl Thresholds {
+    /// Maximum total cost of each node.
+    pub fn with_max_total(mut self, max_total: Option<Duration>) -> Self {
+        self.max_total = max_total;
+        self
+    }
+
+    /// Minimum ratio of successful nodes, in range `0.0..=1.0`.
+    pub fn with_min_success_ratio(mut self, min_success_ratio: Option<f64>) -> Self {
+        self.min_success_ratio = min_success_ratio;
+        self
+    }
+
+    /// The status of successful node, default to 2xx and 3xx if empty.
+    pub fn with_expect_status(mut self, expect_status: Vec<i32>) -> Self {
+        self.expect_status = expect_status;
+        self
+    }
+
+    /// Maximum 95th percentile of total cost of successful nodes.
+    pub fn with_max_p95(mut self, max_p95: Option<Duration>) -> Self {
+        self.max_p95 = max_p95;
+        self
+    }
+
+    pub fn max_total(&self) -> Option<Duration> {
+        self.max_total
+    }
+
+    pub fn min_success_ratio(&self) -> Option<f64> {
+        self.min_success_ratio
+    }
+
+    pub fn expect_status(&self) -> &[i32] {
+        self.expect_status.as_slice()
+    }
+
+    pub fn max_p95(&self) -> Option<Duration> {
+        self.max_p95
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.max_total.is_none()
+            && self.min_success_ratio.is_none()
+            && self.expect_status.is_empty()
+            && self.max_p95.is_none()
+    }
+
+    pub fn validate(&self) -> crate::Result<()> {
+        if let Some(ratio) = self.min_success_ratio {
+            if !(0.0..=1.0).contains(&ratio) {
+                return Err(Error::invalid_param(format!(
+                    "success ratio `{}` not in range 0 to 1",
+                    ratio
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Return true if the status is expected.
+    pub fn is_success(&self, status: i32) -> bool {
+        if self.expect_status.is_empty() {
+            (200..400).contains(&status)
+        } else {
+            self.expect_status.contains(&status)
+        }
+    }
+
+    /// Evaluate the samples, return all the violations.
+    pub fn check(&self, samples: &[Sample]) -> Vec<Violation> {
+        let mut violations = vec![];
+
+        if self.is_empty() {
+            return violations;
+        }
+        if samples.is_empty() {
+            violations.push(Violation::NoSample);
+            return violations;
+        }
+
+        let mut costs = vec![];
+
+        for sample in samples {
+            let success = self.is_success(sample.status);
+
+            if !success && !self.expect_status.is_empty() {
+                violations.push(Violation::Status {
+                    name: sample.name.clone(),
+                    status: sample.status,
+                });
+            }
+            if let (Some(total), Some(max)) = (sample.total, self.max_total) {
+                if total > max {
+                    violations.push(Violation::Total {
+                        name: sample.name.clone(),
+                        total,
+                        max,
+                    });
+                }
+            }
+            if let (true, Some(total)) = (success, sample.total) {
+                costs.push(total);
+            }
+        }
+        if let Some(min) = self.min_success_ratio {
+            let success = samples.iter().filter(|v| self.is_success(v.status)).count();
+            let ratio = success as f64 / samples.len() as f64;
+
+            if ratio < min {
+                violations.push(Violation::SuccessRatio { ratio, min });
+            }
+        }
+        if let Some(max) = self.max_p95 {
+            costs.sort();
+            if let Some(p95) = percentile(&costs, 95.0).filter(|v| *v > max) {
+                violations.push(Violation::P95 { p95, max });
+            }
+        }
+        violations
+    }
+}
+
+/// Parse the status list such as `200,301`.
+pub fn parse_status_list(s: &str) -> crate::Result<Vec<i32>> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            v.parse::<i32>()
+                .map_err(|_| Error::invalid_param(format!("invalid status `{}`", v)))
+        })
+        .collect()
+}
+
+/// The nearest-rank percentile of the sorted values, `p` is in range `0.0..=100.0`.
+pub fn percentile(sorted: &[Duration], p: f64) -> Option<Duration> {
+    if sorted.is_empty() {
+        return None;
+    }
+
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+
+    sorted.get(rank.clamp(1, sorted.len()) - 1).copied()
+}
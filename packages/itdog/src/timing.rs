@@ -0,0 +1,128 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+
+/// A timing reported by itdog, such as `0.123` in seconds.
+///
+/// The original text is kept for display, the duration is `None` if the text can not be parsed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timing {
+    text: String,
+
+    duration: Option<Duration>,
+}
+
+impl Timing {
+    /// Parse the text in seconds, the suffix `s` and `ms` are also accepted.
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let duration = Self::parse(&text);
+
+        Self { text, duration }
+    }
+
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+
+    /// Return the duration in milliseconds, or 0 if the duration is not available.
+    pub fn as_millis(&self) -> u64 {
+        self.duration
+            .map(|v| v.as_millis().try_into().unwrap_or(u64::MAX))
+            .unwrap_or_default()
+    }
+
+    fn parse(text: &str) -> Option<Duration> {
+        let text = text.trim();
+        let (value, scale) = if let Some(value) = text.strip_suffix("ms") {
+            (value, 0.001)
+        } else if let Some(value) = text.strip_suffix('s') {
+            (value, 1.0)
+        } else {
+            (text, 1.0)
+        };
+
+        Self::from_secs(value.trim().parse::<f64>().ok()? * scale)
+    }
+
+    fn from_secs(secs: f64) -> Option<Duration> {
+        Duration::try_from_secs_f64(secs).ok()
+    }
+}
+
+impl From<Duration> for Timing {
+    /// Format the duration same as itdog, i.e. `0.123`.
+    fn from(value: Duration) -> Self {
+        Self {
+            text: format!("{:.3}", value.as_secs_f64()),
+            duration: Some(value),
+        }
+    }
+}
+
+impl From<String> for Timing {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<&str> for Timing {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl Display for Timing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Serialize for Timing {
+    /// Serialize as seconds if the duration is available, otherwise as the original text.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.duration {
+            Some(duration) => serializer.serialize_f64(duration.as_secs_f64()),
+            None if self.text.is_empty() => serializer.serialize_none(),
+            None => serializer.serialize_str(&self.text),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTiming {
+    Text(String),
+
+    Number(f64),
+}
+
+impl<'de> Deserialize<'de> for Timing {
+    /// Accept the string, number and null, never failed with unexpected value.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = Option::<RawTiming>::deserialize(deserializer).unwrap_or(None);
+
+        Ok(match raw {
+            Some(RawTiming::Text(text)) => Self::new(text),
+            Some(RawTiming::Number(secs)) => Self {
+                text: secs.to_string(),
+                duration: Self::from_secs(secs),
+            },
+            None => Self::default(),
+        })
+    }
+}
@@ -0,0 +1,33 @@
+use itdog::Location;
+
+#[test]
+fn parse_location_of_node() {
+    let loc = Location::parse("江苏电信");
+
+    assert_eq!(loc.province(), Some("江苏"));
+    assert_eq!(loc.isp(), Some("电信"));
+    assert_eq!(loc.country(), Some("中国"));
+
+    let loc = Location::parse("黑龙江哈尔滨移动");
+
+    assert_eq!(loc.province(), Some("黑龙江"));
+    assert_eq!(loc.isp(), Some("移动"));
+
+    let loc = Location::parse("中国香港");
+
+    assert_eq!(loc.province(), Some("香港"));
+    assert_eq!(loc.isp(), None);
+    assert_eq!(loc.country(), Some("中国"));
+
+    let loc = Location::parse("深圳联通");
+
+    assert_eq!(loc.province(), None);
+    assert_eq!(loc.isp(), Some("联通"));
+    assert_eq!(loc.country(), Some("中国"));
+
+    let loc = Location::parse("印度尼西亚雅加达");
+
+    assert_eq!(loc.province(), None);
+    assert_eq!(loc.country(), Some("印度尼西亚"));
+    assert_eq!(Location::parse("本机"), Location::default());
+}
@@ -0,0 +1,183 @@
+use itdog::mock::MockServer;
+use itdog::mock::MOCK_TASK_ID;
+use itdog::Error;
+use itdog::ItdogClient;
+use itdog::QueryOptions;
+use itdog::ReqClient;
+use itdog::RetryPolicy;
+use itdog::Timeouts;
+use itdog::DEFAULT_KEY;
+use tokio_util::sync::CancellationToken;
+
+#[tokio::test]
+async fn req_wssocket_msg_with_mock() -> color_eyre::Result<()> {
+    let server = MockServer::builder().start().await?;
+    let cli = reqwest::ClientBuilder::new().cookie_store(true).build()?;
+    let reqc = ReqClient::new(cli, DEFAULT_KEY, "www.example.com");
+    let msg = reqc.req_wssocket_msg(server.endpoint().http_url()).await?;
+    let md5 = ReqClient::generate_md5(MOCK_TASK_ID, DEFAULT_KEY);
+
+    assert_eq!(
+        msg,
+        format!(
+            "{{\"task_id\":\"{}\",\"task_token\":\"{}\"}}",
+            MOCK_TASK_ID,
+            &md5[8..24]
+        )
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn req_wssocket_msg_without_task_id() -> color_eyre::Result<()> {
+    let server = MockServer::builder().with_task_id(None).start().await?;
+    let cli = reqwest::ClientBuilder::new().cookie_store(true).build()?;
+    let reqc = ReqClient::new(cli, DEFAULT_KEY, "www.example.com");
+
+    assert!(matches!(
+        reqc.req_wssocket_msg(server.endpoint().http_url()).await,
+        Err(Error::TaskIdNotFound)
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_with_mock() -> color_eyre::Result<()> {
+    let server = MockServer::builder()
+        .with_message(MockServer::node("江苏电信", "1.1.1.1", 200, "0.123"))
+        .with_message(MockServer::node("广东移动", "2.2.2.2", 502, "1.234"))
+        .start()
+        .await?;
+    let client = ItdogClient::builder()
+        .with_endpoint(server.endpoint())
+        .build()?;
+    let (send, mut recv) = tokio::sync::mpsc::channel(128);
+
+    client
+        .query(
+            "www.example.com",
+            &QueryOptions::default(),
+            CancellationToken::new(),
+            send,
+        )
+        .await?;
+
+    let first = recv.recv().await.unwrap();
+    let second = recv.recv().await.unwrap();
+
+    assert_eq!(first.name(), "江苏电信");
+    assert_eq!(first.http_code(), 200);
+    assert_eq!(first.all_time().text(), "0.123");
+    assert_eq!(
+        first.all_time().duration(),
+        Some(std::time::Duration::from_millis(123))
+    );
+    assert_eq!(second.name(), "广东移动");
+    assert_eq!(second.ip(), "2.2.2.2");
+    assert_eq!(second.http_code(), 502);
+    assert!(recv.recv().await.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_with_invalid_key() -> color_eyre::Result<()> {
+    let server = MockServer::builder()
+        .with_key("another key")
+        .with_message(MockServer::node("江苏电信", "1.1.1.1", 200, "0.123"))
+        .start()
+        .await?;
+    let client = ItdogClient::builder()
+        .with_endpoint(server.endpoint())
+        .build()?;
+    let (send, mut recv) = tokio::sync::mpsc::channel(128);
+
+    // the server closed the websocket, the close frame of client may failed
+    let _ = client
+        .query(
+            "www.example.com",
+            &QueryOptions::default(),
+            CancellationToken::new(),
+            send,
+        )
+        .await;
+
+    assert!(recv.recv().await.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_failed_when_connection_dropped() -> color_eyre::Result<()> {
+    let server = MockServer::builder()
+        .with_message(MockServer::node("江苏电信", "1.1.1.1", 200, "0.123"))
+        .with_disconnect(true)
+        .start()
+        .await?;
+    let client = ItdogClient::builder()
+        .with_endpoint(server.endpoint())
+        .build()?;
+    let (send, mut recv) = tokio::sync::mpsc::channel(128);
+    let options = QueryOptions::default();
+    let ret = client
+        .query("www.example.com", &options, CancellationToken::new(), send)
+        .await;
+
+    // the received message is kept, the error of websocket is returned
+    assert_eq!(recv.recv().await.unwrap().name(), "江苏电信");
+    assert!(matches!(ret, Err(Error::WebSocket(_))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_cancelled_while_waiting_reply() -> color_eyre::Result<()> {
+    let server = MockServer::builder()
+        .with_message(MockServer::node("江苏电信", "1.1.1.1", 200, "0.123"))
+        .with_finished(false)
+        .start()
+        .await?;
+    let client = ItdogClient::builder()
+        .with_endpoint(server.endpoint())
+        .build()?;
+    let cancell = CancellationToken::new();
+    let (send, mut recv) = tokio::sync::mpsc::channel(128);
+    let options = QueryOptions::default();
+    let query = client.query("www.example.com", &options, cancell.clone(), send);
+    let cancel = async {
+        // cancel after the first message, the server never send the finished message
+        let first = recv.recv().await;
+
+        cancell.cancel();
+        first
+    };
+    let (ret, first) = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        tokio::join!(query, cancel)
+    })
+    .await?;
+
+    assert_eq!(first.unwrap().name(), "江苏电信");
+    assert!(matches!(ret, Err(Error::Cancelled)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_timeout_waiting_first_message() -> color_eyre::Result<()> {
+    let server = MockServer::builder().with_finished(false).start().await?;
+    let client = ItdogClient::builder()
+        .with_endpoint(server.endpoint())
+        .with_timeouts(
+            Timeouts::default().with_first_message(Some(std::time::Duration::from_millis(200))),
+        )
+        .with_retry(RetryPolicy::never())
+        .build()?;
+    let (send, _recv) = tokio::sync::mpsc::channel(128);
+    let ret = client
+        .query(
+            "www.example.com",
+            &QueryOptions::default(),
+            CancellationToken::new(),
+            send,
+        )
+        .await;
+
+    assert!(matches!(ret, Err(Error::Timeout("first message"))));
+    Ok(())
+}
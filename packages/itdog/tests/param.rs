@@ -0,0 +1,63 @@
+use itdog::CheckMode;
+use itdog::DnsServerType;
+use itdog::Error;
+use itdog::HttpMethod;
+use itdog::Line;
+
+#[test]
+fn parse_and_display_check_mode() -> itdog::Result<()> {
+    assert_eq!("fast".parse::<CheckMode>()?, CheckMode::Fast);
+    assert_eq!("SLOW".parse::<CheckMode>()?, CheckMode::Slow);
+    assert_eq!(CheckMode::Slow.to_string(), "slow");
+    assert_eq!(CheckMode::default(), CheckMode::Fast);
+    assert!(matches!(
+        "quick".parse::<CheckMode>(),
+        Err(Error::InvalidParam(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn parse_and_display_http_method() -> itdog::Result<()> {
+    assert_eq!("get".parse::<HttpMethod>()?, HttpMethod::Get);
+    assert_eq!("POST".parse::<HttpMethod>()?, HttpMethod::Post);
+    assert_eq!("head".parse::<HttpMethod>()?, HttpMethod::Head);
+    assert_eq!(HttpMethod::Head.to_string(), "head");
+    assert_eq!(http::Method::from(HttpMethod::Post), http::Method::POST);
+    assert!("put".parse::<HttpMethod>().is_err());
+    Ok(())
+}
+
+#[test]
+fn parse_and_display_dns_server_type() -> itdog::Result<()> {
+    assert_eq!("isp".parse::<DnsServerType>()?, DnsServerType::Isp);
+    assert_eq!("Custom".parse::<DnsServerType>()?, DnsServerType::Custom);
+    assert_eq!(DnsServerType::Custom.to_string(), "custom");
+    assert!("google".parse::<DnsServerType>().is_err());
+    Ok(())
+}
+
+#[test]
+fn parse_and_display_line() -> itdog::Result<()> {
+    assert_eq!("".parse::<Line>()?, Line::All);
+    assert_eq!("telecom".parse::<Line>()?, Line::Telecom);
+    assert_eq!("联通".parse::<Line>()?, Line::Unicom);
+    assert_eq!("3".parse::<Line>()?, Line::Mobile);
+    assert_eq!("Overseas".parse::<Line>()?, Line::Overseas);
+    assert_eq!(Line::Mobile.to_string(), "mobile");
+    assert!("4".parse::<Line>().is_err());
+    Ok(())
+}
+
+#[test]
+fn serialize_as_itdog_value() -> serde_json::Result<()> {
+    assert_eq!(serde_json::to_string(&Line::All)?, r#""""#);
+    assert_eq!(serde_json::to_string(&Line::Overseas)?, r#""5""#);
+    assert_eq!(serde_json::to_string(&CheckMode::Slow)?, r#""slow""#);
+    assert_eq!(serde_json::to_string(&HttpMethod::Post)?, r#""post""#);
+    assert_eq!(
+        serde_json::to_string(&DnsServerType::Custom)?,
+        r#""custom""#
+    );
+    Ok(())
+}
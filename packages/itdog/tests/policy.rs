@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use itdog::parse_duration;
+use itdog::RetryPolicy;
+
+#[test]
+fn parse_duration_with_unit() -> color_eyre::Result<()> {
+    assert_eq!(parse_duration("500ms")?, Some(Duration::from_millis(500)));
+    assert_eq!(parse_duration("10")?, Some(Duration::from_secs(10)));
+    assert_eq!(parse_duration("1.5s")?, Some(Duration::from_millis(1500)));
+    assert_eq!(parse_duration("2m")?, Some(Duration::from_secs(120)));
+    assert_eq!(parse_duration("0")?, None);
+    assert_eq!(parse_duration("off")?, None);
+    assert!(parse_duration("10x").is_err());
+    assert!(parse_duration("ms").is_err());
+    Ok(())
+}
+
+#[test]
+fn retry_backoff_is_capped() {
+    let retry = RetryPolicy::default()
+        .with_initial_backoff(Duration::from_millis(500))
+        .with_max_backoff(Duration::from_secs(3));
+
+    assert_eq!(retry.backoff(0), Duration::from_millis(500));
+    assert_eq!(retry.backoff(1), Duration::from_secs(1));
+    assert_eq!(retry.backoff(2), Duration::from_secs(2));
+    assert_eq!(retry.backoff(3), Duration::from_secs(3));
+    assert_eq!(retry.backoff(64), Duration::from_secs(3));
+}
@@ -0,0 +1,28 @@
+use itdog::parse_targets;
+use itdog::HttpMethod;
+use itdog::Target;
+
+#[test]
+fn parse_targets_with_overrides() -> color_eyre::Result<()> {
+    let text =
+        "# comment\n\nwww.baidu.com\n  example.com method=head status=200,301 server=tcping  \n";
+
+    assert_eq!(
+        parse_targets(text)?,
+        vec![
+            Target::new("www.baidu.com"),
+            Target::new("example.com")
+                .with_method(Some(HttpMethod::Head))
+                .with_expect_status(vec![200, 301])
+                .with_server(Some("tcping".to_string())),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn parse_targets_invalid_override() {
+    assert!(parse_targets("www.baidu.com timeout=1s").is_err());
+    assert!(parse_targets("www.baidu.com method").is_err());
+    assert!(parse_targets("www.baidu.com method=put").is_err());
+}
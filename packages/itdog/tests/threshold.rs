@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use itdog::parse_status_list;
+use itdog::percentile;
+use itdog::Sample;
+use itdog::Thresholds;
+use itdog::Violation;
+
+fn samples() -> Vec<Sample> {
+    vec![
+        Sample::new("a", 200, Some(Duration::from_millis(100))),
+        Sample::new("b", 301, Some(Duration::from_millis(300))),
+        Sample::new("c", 502, Some(Duration::from_millis(50))),
+        Sample::new("d", 0, None),
+    ]
+}
+
+#[test]
+fn no_threshold_never_violated() {
+    assert!(Thresholds::default().check(&[]).is_empty());
+    assert!(Thresholds::default().check(&samples()).is_empty());
+}
+
+#[test]
+fn check_status_and_total() -> color_eyre::Result<()> {
+    let thresholds = Thresholds::default()
+        .with_expect_status(parse_status_list("200, 301")?)
+        .with_max_total(Some(Duration::from_millis(200)));
+
+    assert_eq!(
+        thresholds.check(&samples()),
+        vec![
+            Violation::Total {
+                name: "b".to_string(),
+                total: Duration::from_millis(300),
+                max: Duration::from_millis(200),
+            },
+            Violation::Status {
+                name: "c".to_string(),
+                status: 502,
+            },
+            Violation::Status {
+                name: "d".to_string(),
+                status: 0,
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn check_success_ratio_and_p95() {
+    let thresholds = Thresholds::default()
+        .with_min_success_ratio(Some(0.75))
+        .with_max_p95(Some(Duration::from_millis(250)));
+
+    assert_eq!(
+        thresholds.check(&samples()),
+        vec![
+            Violation::SuccessRatio {
+                ratio: 0.5,
+                min: 0.75
+            },
+            Violation::P95 {
+                p95: Duration::from_millis(300),
+                max: Duration::from_millis(250),
+            },
+        ]
+    );
+    assert_eq!(thresholds.check(&[]), vec![Violation::NoSample]);
+}
+
+#[test]
+fn nearest_rank_percentile() {
+    let values: Vec<_> = (1..=20).map(Duration::from_secs).collect();
+
+    assert_eq!(percentile(&[], 95.0), None);
+    assert_eq!(percentile(&values, 95.0), Some(Duration::from_secs(19)));
+    assert_eq!(percentile(&values, 50.0), Some(Duration::from_secs(10)));
+    assert_eq!(percentile(&values, 0.0), Some(Duration::from_secs(1)));
+    assert_eq!(percentile(&values, 100.0), Some(Duration::from_secs(20)));
+}